@@ -0,0 +1,197 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::integrator::{Integrator, IntegratorState};
+use crate::Body;
+
+const CHECKPOINT_MAGIC: &[u8; 4] = b"BPCK";
+const CHECKPOINT_VERSION: u16 = 2;
+
+/// The parameters of a run, which stay the same over it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunConfig {
+    pub integrator: Integrator,
+    /// The simulated time of a step in seconds.
+    pub step: f64,
+    /// The seed the initial bodies were generated with, if they were.
+    pub seed: Option<u64>,
+}
+
+impl RunConfig {
+    pub const fn new(integrator: Integrator, step: f64, seed: Option<u64>) -> Self {
+        Self { integrator, step, seed }
+    }
+}
+
+/// A run advancing the bodies by fixed steps, which can be saved to a checkpoint and resumed from it, continuing
+/// exactly as if it had never been interrupted.
+///
+/// The bodies, the state the integrator carries between the steps and the number of steps taken are all the state of
+/// the run. The time is derived from the latter rather than summed, so that it does not depend on where the run was
+/// split either.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    config: RunConfig,
+    bodies: Vec<Body>,
+    integrator_state: IntegratorState,
+    /// The simulated time the run started at in seconds.
+    time_start: f64,
+    step_count: u64,
+}
+
+impl Run {
+    pub fn new(bodies: Vec<Body>, config: RunConfig, time_start: f64) -> Self {
+        Self { config, bodies, integrator_state: IntegratorState::default(), time_start, step_count: 0 }
+    }
+
+    pub fn config(&self) -> &RunConfig {
+        &self.config
+    }
+
+    pub fn bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Returns the simulated time in seconds.
+    pub fn time(&self) -> f64 {
+        self.time_start + self.step_count as f64 * self.config.step
+    }
+
+    pub fn advance(&mut self, step_count: u64) {
+        for _ in 0..step_count {
+            (self.bodies, _) = self.config.integrator
+                .integrate_continuing(&self.bodies, self.config.step, &mut self.integrator_state);
+        }
+        self.step_count += step_count;
+    }
+
+    /// Writes the checkpoint in a little-endian binary format keeping the exact bits of the floats: the magic `BPCK`,
+    /// the version (u16), the index of the integrator in [`Integrator::ALL`] (u8), the step (f64), whether there is a
+    /// seed (u8) followed by it (u64) if so, whether the integrator carries a substep (u8) followed by it (f64) if so,
+    /// the start time (f64), the number of steps taken (u64), the number of bodies (u64) and each body, its mass,
+    /// position and velocity (5 f64).
+    pub fn write_checkpoint(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(CHECKPOINT_MAGIC)?;
+        writer.write_all(&CHECKPOINT_VERSION.to_le_bytes())?;
+
+        let integrator_index = Integrator::ALL.iter().position(|&integrator| integrator == self.config.integrator).unwrap();
+        writer.write_all(&[integrator_index as u8])?;
+        writer.write_all(&self.config.step.to_le_bytes())?;
+        match self.config.seed {
+            Some(seed) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&seed.to_le_bytes())?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+        match self.integrator_state.substep {
+            Some(substep) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&substep.to_le_bytes())?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+
+        writer.write_all(&self.time_start.to_le_bytes())?;
+        writer.write_all(&self.step_count.to_le_bytes())?;
+        writer.write_all(&(self.bodies.len() as u64).to_le_bytes())?;
+        for body in self.bodies.iter() {
+            for value in [body.mass, body.position.x, body.position.y, body.velocity.x, body.velocity.y] {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a checkpoint written by [`Self::write_checkpoint`].
+    pub fn read_checkpoint(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(invalid_data("not a checkpoint".to_string()));
+        }
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != CHECKPOINT_VERSION {
+            return Err(invalid_data(format!("unsupported checkpoint version {version}")));
+        }
+
+        let integrator_index = read_u8(&mut reader)?;
+        let integrator = *Integrator::ALL.get(integrator_index as usize)
+            .ok_or_else(|| invalid_data(format!("unknown integrator {integrator_index}")))?;
+        let step = read_f64(&mut reader)?;
+        let seed = match read_u8(&mut reader)? {
+            0 => None,
+            1 => Some(read_u64(&mut reader)?),
+            flag => return Err(invalid_data(format!("invalid seed flag {flag}"))),
+        };
+        let substep = match read_u8(&mut reader)? {
+            0 => None,
+            1 => Some(read_f64(&mut reader)?),
+            flag => return Err(invalid_data(format!("invalid substep flag {flag}"))),
+        };
+
+        let time_start = read_f64(&mut reader)?;
+        let step_count = read_u64(&mut reader)?;
+        let body_count = read_u64(&mut reader)?;
+        // growing as read rather than trusting the count to allocate
+        let mut bodies = Vec::new();
+        for _ in 0..body_count {
+            let mut values = [0f64; 5];
+            for value in values.iter_mut() {
+                *value = read_f64(&mut reader)?;
+            }
+            let [mass, x, y, vx, vy] = values;
+            bodies.push(Body::new(mass, Vector2::new(x, y), Vector2::new(vx, vy)));
+        }
+
+        Ok(Self {
+            config: RunConfig::new(integrator, step, seed),
+            bodies,
+            integrator_state: IntegratorState { substep },
+            time_start,
+            step_count,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_checkpoint(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_checkpoint(BufReader::new(File::open(path)?))
+    }
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut bytes = [0u8; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_f64(reader: &mut impl Read) -> io::Result<f64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,33 @@
+use nalgebra::Vector2;
+
+use crate::{Body, BODY_DISTANCE_MIN, GRAVITATIONAL_CONSTANT};
+
+/// The gravitational field of a set of bodies at a single point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldSample {
+    /// The potential energy per unit mass.
+    pub potential: f64,
+    /// The acceleration of a test particle, i.e. the field intensity.
+    pub acceleration: Vector2<f64>,
+}
+
+impl FieldSample {
+    pub fn new(bodies: &[Body], point: Vector2<f64>) -> Self {
+        bodies.iter().fold(
+            Self { potential: 0f64, acceleration: Vector2::zeros() },
+            |sample, body| {
+                let offset = body.position - point;
+                let distance = offset.norm().max(BODY_DISTANCE_MIN);
+                Self {
+                    potential: sample.potential - GRAVITATIONAL_CONSTANT * body.mass / distance,
+                    acceleration: sample.acceleration + GRAVITATIONAL_CONSTANT * body.mass * offset / distance.powi(3),
+                }
+            },
+        )
+    }
+}
+
+/// Evaluates the field of the bodies at each of the points.
+pub fn evaluate_field(bodies: &[Body], points: &[Vector2<f64>]) -> Vec<FieldSample> {
+    points.iter().map(|&point| FieldSample::new(bodies, point)).collect()
+}
@@ -0,0 +1,112 @@
+use std::f64::consts::TAU;
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::circular_speed;
+use crate::kepler::OrbitalElements;
+use crate::{Body, GRAVITATIONAL_CONSTANT};
+
+/// The number of rings the particles of a disc are placed on, evenly spaced up to its radius.
+const DISC_RING_COUNT: usize = 5;
+
+/// The sense a disc rotates in relative to the orbit of the galaxies. As the motion is planar, the discs lie in the
+/// orbital plane, so that an inclination of 0° (prograde) or 180° (retrograde) are the only ones possible.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Spin {
+    #[default]
+    Prograde,
+    Retrograde,
+}
+
+/// A central mass orbited by a disc of massless test particles on circular orbits, as in the models of Toomre and
+/// Toomre (1972).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiscGalaxy {
+    pub mass: f64,
+    /// The radius of the outermost ring.
+    pub radius: f64,
+    pub particle_count: usize,
+    pub spin: Spin,
+}
+
+impl DiscGalaxy {
+    pub const fn new(mass: f64, radius: f64, particle_count: usize, spin: Spin) -> Self {
+        Self { mass, radius, particle_count, spin }
+    }
+
+    /// Returns the central body followed by the particles, moving with it. The counts of the particles on the rings
+    /// grow with their radii, so that the rings are about as dense.
+    pub fn bodies(&self, position: Vector2<f64>, velocity: Vector2<f64>, counterclockwise: bool) -> Vec<Body> {
+        let direction = if counterclockwise == (self.spin == Spin::Prograde) { 1f64 } else { -1f64 };
+        let weight_total = DISC_RING_COUNT * (DISC_RING_COUNT + 1) / 2;
+        // the number of particles on the rings up to the one, distributing the rounding over them
+        let count_within = |ring: usize| self.particle_count * ring * (ring + 1) / 2 / weight_total;
+
+        let mut bodies = vec![Body::new(self.mass, position, velocity)];
+        for ring in 1..=DISC_RING_COUNT {
+            let count = count_within(ring) - count_within(ring - 1);
+            let radius = self.radius * ring as f64 / DISC_RING_COUNT as f64;
+            let speed = circular_speed(self.mass, radius);
+            // turned by half a spacing on every other ring
+            let offset = 0.5f64 * (ring % 2) as f64;
+
+            bodies.extend((0..count).map(|index| {
+                let angle = TAU * (index as f64 + offset) / count as f64;
+                let radial = Vector2::new(angle.cos(), angle.sin());
+                Body::new(
+                    0f64,
+                    position + radius * radial,
+                    velocity + direction * speed * Vector2::new(-radial.y, radial.x),
+                )
+            }));
+        }
+        bodies
+    }
+}
+
+/// Two disc galaxies approaching each other on a Kepler orbit of their centres, counterclockwise with the pericentre
+/// on the positive x axis, e.g. to reproduce the tidal tails and bridges of interacting galaxies.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GalaxyCollision {
+    pub galaxies: [DiscGalaxy; 2],
+    pub pericentre_distance: f64,
+    /// 1 for the parabolic encounters of Toomre and Toomre, less for bound orbits and more for hyperbolic ones.
+    pub eccentricity: f64,
+    /// The distance between the centres at the start, before the pericentre. Bound orbits start at most at the
+    /// apocentre.
+    pub distance_start: f64,
+}
+
+impl GalaxyCollision {
+    pub const fn new(galaxies: [DiscGalaxy; 2], pericentre_distance: f64, eccentricity: f64, distance_start: f64) -> Self {
+        Self { galaxies, pericentre_distance, eccentricity, distance_start }
+    }
+
+    /// Returns the bodies of the first galaxy followed by those of the second one, each starting with its central
+    /// body, in the frame of the centre of mass.
+    pub fn bodies(&self) -> Vec<Body> {
+        let [galaxy1, galaxy2] = self.galaxies;
+        let mass_total = galaxy1.mass + galaxy2.mass;
+        let orbit = OrbitalElements {
+            semi_latus_rectum: self.pericentre_distance * (1f64 + self.eccentricity),
+            eccentricity: self.eccentricity,
+            argument_of_periapsis: 0f64,
+            true_anomaly: 0f64,
+            counterclockwise: true,
+        };
+
+        // approaching the pericentre
+        let true_anomaly = if self.eccentricity > 0f64 {
+            -((orbit.semi_latus_rectum / self.distance_start - 1f64) / self.eccentricity).clamp(-1f64, 1f64).acos()
+        } else {
+            0f64
+        };
+        let position = orbit.position(true_anomaly);
+        let velocity = orbit.velocity(true_anomaly, GRAVITATIONAL_CONSTANT * mass_total);
+
+        let mut bodies = galaxy1.bodies(-galaxy2.mass / mass_total * position, -galaxy2.mass / mass_total * velocity, true);
+        bodies.extend(galaxy2.bodies(galaxy1.mass / mass_total * position, galaxy1.mass / mass_total * velocity, true));
+        bodies
+    }
+}
@@ -0,0 +1,190 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::random::Random;
+use crate::{kinetic_energy, potential_energy, Body, GRAVITATIONAL_CONSTANT};
+
+/// The part of the total mass of a Keplerian ring in its central body.
+const RING_CENTRAL_MASS_FRACTION: f64 = 0.99;
+/// Half the width of a Keplerian ring relative to its radius.
+const RING_HALF_WIDTH: f64 = 0.1;
+/// The part of the mass of a Plummer sphere left out at its far outskirts, where single bodies would lie very far away.
+const PLUMMER_MASS_CUT: f64 = 0.001;
+
+/// How the generated bodies are distributed around the origin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Distribution {
+    /// A Plummer sphere with the scale radius projected onto the plane, its velocities scaled so that the projected
+    /// bodies are in virial equilibrium.
+    #[default]
+    PlummerSphere,
+    /// A disc of the radius of uniform surface density, rotating at the circular velocity of the mass within.
+    UniformDisc,
+    /// A narrow ring of the radius on circular orbits around a central body holding most of the mass.
+    KeplerianRing,
+    /// A disc whose surface density falls off exponentially with the scale length, rotating at the circular velocity
+    /// of the mass within.
+    ExponentialDisc,
+}
+
+impl Distribution {
+    pub const ALL: [Self; 4] = [Self::PlummerSphere, Self::UniformDisc, Self::KeplerianRing, Self::ExponentialDisc];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PlummerSphere => "Plummer sphere",
+            Self::UniformDisc => "uniform disc",
+            Self::KeplerianRing => "Keplerian ring",
+            Self::ExponentialDisc => "exponential disc",
+        }
+    }
+}
+
+/// Generates the initial conditions of many bodies, the same ones for the same seed. The discs and the ring rotate
+/// counterclockwise and the bodies are moved to their centre of mass frame.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Generator {
+    pub distribution: Distribution,
+    /// The number of bodies, including the central one of a ring.
+    pub count: usize,
+    pub mass_total: f64,
+    pub radius_scale: f64,
+    pub seed: u64,
+}
+
+impl Generator {
+    pub const fn new(distribution: Distribution, count: usize, mass_total: f64, radius_scale: f64, seed: u64) -> Self {
+        Self { distribution, count, mass_total, radius_scale, seed }
+    }
+
+    pub fn generate(&self) -> Vec<Body> {
+        if self.count == 0 {
+            return Vec::new();
+        }
+
+        let mut random = Random::new(self.seed);
+        let mut bodies = match self.distribution {
+            Distribution::PlummerSphere => self.plummer_sphere(&mut random),
+            Distribution::UniformDisc => self.disc(&mut random, |random, radius_scale| radius_scale * random.uniform().sqrt(),
+                |radius, radius_scale| (radius / radius_scale).powi(2).min(1f64)),
+            Distribution::KeplerianRing => self.keplerian_ring(&mut random),
+            Distribution::ExponentialDisc => self.disc(&mut random, exponential_disc_radius,
+                |radius, radius_scale| 1f64 - (1f64 + radius / radius_scale) * (-radius / radius_scale).exp()),
+        };
+        to_centre_of_mass_frame(&mut bodies);
+        if self.distribution == Distribution::PlummerSphere {
+            to_virial_equilibrium(&mut bodies);
+        }
+        bodies
+    }
+
+    fn plummer_sphere(&self, random: &mut Random) -> Vec<Body> {
+        let mass = self.mass_total / self.count as f64;
+        let velocity_scale = (GRAVITATIONAL_CONSTANT * self.mass_total / self.radius_scale).sqrt();
+
+        (0..self.count)
+            .map(|_| {
+                let mass_fraction = (1f64 - PLUMMER_MASS_CUT) * random.uniform_positive();
+                let radius = self.radius_scale / (mass_fraction.powf(-2f64 / 3f64) - 1f64).sqrt();
+
+                // the ratio of the speed to the escape velocity by rejection sampling (Aarseth, Hénon and Wielen 1974)
+                let speed_ratio = loop {
+                    let ratio = random.uniform();
+                    if 0.1 * random.uniform() < ratio.powi(2) * (1f64 - ratio.powi(2)).powf(3.5) {
+                        break ratio;
+                    }
+                };
+                let speed_escape = 2f64.sqrt() * velocity_scale * (1f64 + (radius / self.radius_scale).powi(2)).powf(-0.25);
+
+                Body::new(mass, radius * projected_direction(random), speed_ratio * speed_escape * projected_direction(random))
+            })
+            .collect()
+    }
+
+    /// Generates a rotating disc from the distribution of the radii and the part of the mass within a radius.
+    fn disc(
+        &self,
+        random: &mut Random,
+        radius: impl Fn(&mut Random, f64) -> f64,
+        mass_fraction_within: impl Fn(f64, f64) -> f64,
+    ) -> Vec<Body> {
+        let mass = self.mass_total / self.count as f64;
+
+        (0..self.count)
+            .map(|_| {
+                let radius = radius(random, self.radius_scale);
+                let direction = random.direction();
+                let speed = circular_speed(self.mass_total * mass_fraction_within(radius, self.radius_scale), radius);
+                Body::new(mass, radius * direction, speed * Vector2::new(-direction.y, direction.x))
+            })
+            .collect()
+    }
+
+    fn keplerian_ring(&self, random: &mut Random) -> Vec<Body> {
+        let ring_count = self.count - 1;
+        let mass_central = if ring_count > 0 {
+            self.mass_total * RING_CENTRAL_MASS_FRACTION
+        } else {
+            self.mass_total
+        };
+        let mass = (self.mass_total - mass_central) / ring_count.max(1) as f64;
+
+        let mut bodies = vec![Body::new(mass_central, Vector2::zeros(), Vector2::zeros())];
+        bodies.extend((0..ring_count).map(|_| {
+            let radius = self.radius_scale * (1f64 + RING_HALF_WIDTH * (2f64 * random.uniform() - 1f64));
+            let direction = random.direction();
+            let speed = circular_speed(mass_central, radius);
+            Body::new(mass, radius * direction, speed * Vector2::new(-direction.y, direction.x))
+        }));
+        bodies
+    }
+}
+
+/// Returns the speed of a circular orbit at the radius around the mass, taken as lying at the centre.
+pub(crate) fn circular_speed(mass_within: f64, radius: f64) -> f64 {
+    if radius > 0f64 {
+        (GRAVITATIONAL_CONSTANT * mass_within / radius).sqrt()
+    } else {
+        0f64
+    }
+}
+
+/// Returns a radius distributed like the surface density of an exponential disc, r e^(-r/h), the gamma distribution
+/// of shape 2.
+pub(crate) fn exponential_disc_radius(random: &mut Random, radius_scale: f64) -> f64 {
+    -radius_scale * (random.uniform_positive() * random.uniform_positive()).ln()
+}
+
+/// Returns the projection onto the plane of a unit vector in a uniformly distributed direction in space.
+fn projected_direction(random: &mut Random) -> Vector2<f64> {
+    let z = 2f64 * random.uniform() - 1f64;
+    (1f64 - z * z).sqrt() * random.direction()
+}
+
+/// Scales the velocities so that twice the kinetic energy equals the magnitude of the potential one, as the projection
+/// onto the plane brings the bodies closer together and slows them down, unlike in space.
+fn to_virial_equilibrium(bodies: &mut [Body]) {
+    let kinetic_energy = kinetic_energy(bodies);
+    if kinetic_energy <= 0f64 {
+        return;
+    }
+
+    let scale = (-potential_energy(bodies) / (2f64 * kinetic_energy)).sqrt();
+    for body in bodies.iter_mut() {
+        body.velocity *= scale;
+    }
+}
+
+fn to_centre_of_mass_frame(bodies: &mut [Body]) {
+    let mass_total = bodies.iter().map(|body| body.mass).sum::<f64>();
+    if mass_total <= 0f64 {
+        return;
+    }
+
+    let position = bodies.iter().map(|body| body.mass * body.position).sum::<Vector2<f64>>() / mass_total;
+    let velocity = bodies.iter().map(|body| body.mass * body.velocity).sum::<Vector2<f64>>() / mass_total;
+    for body in bodies.iter_mut() {
+        body.position -= position;
+        body.velocity -= velocity;
+    }
+}
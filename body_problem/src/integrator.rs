@@ -0,0 +1,184 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::{accelerations, accelerations_and_jerks, simulate, Body};
+
+/// The accuracy parameter of the Aarseth criterion choosing the substeps of the Hermite method, the smaller the
+/// shorter.
+const HERMITE_ACCURACY: f64 = 0.02;
+/// The accuracy parameter of the first substep, estimated from the accelerations and the jerks alone.
+const HERMITE_ACCURACY_START: f64 = 0.01;
+/// The shortest substep relative to the step, bounding the number of substeps of a step when the bodies nearly
+/// coincide.
+const HERMITE_SUBSTEP_MIN_FRACTION: f64 = 1e-4;
+
+/// The numerical method advancing the bodies by a step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Integrator {
+    /// The first-order symplectic method of [`simulate`].
+    #[default]
+    SemiImplicitEuler,
+    /// The second-order symplectic (leapfrog) method, evaluating the accelerations twice per step.
+    VelocityVerlet,
+    /// The classical fourth-order Runge-Kutta method, evaluating the accelerations four times per step.
+    RungeKutta4,
+    /// The fourth-order Hermite predictor-corrector method using the jerks, dividing the step into substeps chosen by
+    /// the Aarseth criterion, shorter during close encounters.
+    Hermite,
+}
+
+/// What an integrator carries over from a step to the next one, part of the state of a simulation along with the
+/// bodies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntegratorState {
+    /// The substep of the Hermite method chosen at the end of the previous step, unless the bodies moved uniformly.
+    pub(crate) substep: Option<f64>,
+}
+
+impl Integrator {
+    pub const ALL: [Self; 4] = [Self::SemiImplicitEuler, Self::VelocityVerlet, Self::RungeKutta4, Self::Hermite];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SemiImplicitEuler => "semi-implicit Euler",
+            Self::VelocityVerlet => "velocity Verlet",
+            Self::RungeKutta4 => "Runge-Kutta 4",
+            Self::Hermite => "Hermite 4",
+        }
+    }
+
+    /// Advances the bodies by a step as the first one of a simulation.
+    pub fn integrate(&self, bodies: &[Body], step: f64) -> Vec<Body> {
+        self.integrate_continuing(bodies, step, &mut IntegratorState::default()).0
+    }
+
+    /// Advances the bodies by a step continuing from the state the previous one left, returning them and the number of
+    /// substeps taken, one unless the method divides the step.
+    pub fn integrate_continuing(&self, bodies: &[Body], step: f64, state: &mut IntegratorState) -> (Vec<Body>, u64) {
+        match self {
+            Self::SemiImplicitEuler => (simulate(bodies, step), 1),
+            Self::VelocityVerlet => (velocity_verlet(bodies, step), 1),
+            Self::RungeKutta4 => (runge_kutta_4(bodies, step), 1),
+            Self::Hermite => hermite(bodies, step, state),
+        }
+    }
+}
+
+fn velocity_verlet(bodies: &[Body], step: f64) -> Vec<Body> {
+    let mut bodies_new = bodies.to_vec();
+
+    for (body, acceleration) in bodies_new.iter_mut().zip(accelerations(bodies)) {
+        body.velocity += acceleration * step / 2f64;
+        body.position += body.velocity * step;
+    }
+
+    let accelerations_new = accelerations(&bodies_new);
+    for (body, acceleration) in bodies_new.iter_mut().zip(accelerations_new) {
+        body.velocity += acceleration * step / 2f64;
+    }
+
+    bodies_new
+}
+
+fn runge_kutta_4(bodies: &[Body], step: f64) -> Vec<Body> {
+    // the derivatives of the positions and the velocities at a state offset from the initial one
+    let derivatives = |offsets: &[(Vector2<f64>, Vector2<f64>)], scale: f64| {
+        let bodies_offset = bodies.iter().zip(offsets.iter())
+            .map(|(body, (position_offset, velocity_offset))| Body::new(
+                body.mass,
+                body.position + position_offset * scale,
+                body.velocity + velocity_offset * scale,
+            ))
+            .collect::<Vec<_>>();
+
+        bodies_offset.iter().zip(accelerations(&bodies_offset))
+            .map(|(body, acceleration)| (body.velocity, acceleration))
+            .collect::<Vec<_>>()
+    };
+
+    let zero = vec![(Vector2::zeros(), Vector2::zeros()); bodies.len()];
+    let k1 = derivatives(&zero, 0f64);
+    let k2 = derivatives(&k1, step / 2f64);
+    let k3 = derivatives(&k2, step / 2f64);
+    let k4 = derivatives(&k3, step);
+
+    bodies.iter().enumerate()
+        .map(|(index, body)| Body::new(
+            body.mass,
+            body.position + (k1[index].0 + 2f64 * k2[index].0 + 2f64 * k3[index].0 + k4[index].0) * step / 6f64,
+            body.velocity + (k1[index].1 + 2f64 * k2[index].1 + 2f64 * k3[index].1 + k4[index].1) * step / 6f64,
+        ))
+        .collect()
+}
+
+fn hermite(bodies: &[Body], step: f64, state: &mut IntegratorState) -> (Vec<Body>, u64) {
+    let mut bodies = bodies.to_vec();
+    let mut derivatives = accelerations_and_jerks(&bodies);
+    // The jerks vanish for the bodies at rest, so the time to fall the distance to the nearest body bounds the first
+    // substep too.
+    let mut substep = state.substep.or_else(|| substep_min(bodies.iter().zip(derivatives.iter())
+        .flat_map(|(body, (acceleration, jerk))| {
+            let distance_nearest = bodies.iter()
+                .map(|other| (other.position - body.position).norm())
+                .filter(|&distance| distance > 0f64)
+                .fold(f64::INFINITY, f64::min);
+            [
+                HERMITE_ACCURACY_START * acceleration.norm() / jerk.norm(),
+                HERMITE_ACCURACY_START * (distance_nearest / acceleration.norm()).sqrt(),
+            ]
+        })));
+    let mut time = 0f64;
+    let mut substeps = 0;
+
+    loop {
+        // The rest of the step is divided evenly, so that the last substep ends at the step exactly without being cut
+        // so short that the criterion after it would be lost in the rounding errors.
+        let substep_count = substep
+            .map(|substep| ((step - time) / substep.max(step * HERMITE_SUBSTEP_MIN_FRACTION)).ceil().max(1f64))
+            .unwrap_or(1f64);
+        let last = substep_count == 1f64;
+        let dt = (step - time) / substep_count;
+
+        let bodies_predicted = bodies.iter().zip(derivatives.iter())
+            .map(|(body, (acceleration, jerk))| Body::new(
+                body.mass,
+                body.position + dt * (body.velocity + dt * (acceleration / 2f64 + dt * jerk / 6f64)),
+                body.velocity + dt * (acceleration + dt * jerk / 2f64),
+            ))
+            .collect::<Vec<_>>();
+        let derivatives_new = accelerations_and_jerks(&bodies_predicted);
+
+        for (body, ((acceleration, jerk), (acceleration_new, jerk_new))) in bodies.iter_mut()
+            .zip(derivatives.iter().zip(derivatives_new.iter())) {
+            let velocity = body.velocity + (acceleration + acceleration_new) * dt / 2f64
+                + (jerk - jerk_new) * dt.powi(2) / 12f64;
+            body.position += (body.velocity + velocity) * dt / 2f64 + (acceleration - acceleration_new) * dt.powi(2) / 12f64;
+            body.velocity = velocity;
+        }
+        substeps += 1;
+
+        // the Aarseth criterion with the third and the second derivatives of the accelerations at the end of the
+        // substep, from the Hermite interpolation
+        substep = substep_min(derivatives.iter().zip(derivatives_new.iter())
+            .map(|((acceleration, jerk), (acceleration_new, jerk_new))| {
+                let crackle = 12f64 * (acceleration - acceleration_new) / dt.powi(3) + 6f64 * (jerk + jerk_new) / dt.powi(2);
+                let snap = -6f64 * (acceleration - acceleration_new) / dt.powi(2) - 2f64 * (2f64 * jerk + jerk_new) / dt
+                    + crackle * dt;
+                (HERMITE_ACCURACY * (acceleration_new.norm() * snap.norm() + jerk_new.norm_squared())
+                    / (jerk_new.norm() * crackle.norm() + snap.norm_squared())).sqrt()
+            }));
+        if last {
+            state.substep = substep;
+            return (bodies, substeps);
+        }
+        derivatives = derivatives_new;
+        time += dt;
+    }
+}
+
+/// Returns the shortest of the substeps of the bodies, ignoring those of the bodies moving uniformly.
+fn substep_min(substeps: impl Iterator<Item = f64>) -> Option<f64> {
+    substeps
+        .filter(|substep| substep.is_finite() && *substep > 0f64)
+        .min_by(f64::total_cmp)
+}
@@ -0,0 +1,114 @@
+use std::f64::consts::PI;
+
+use nalgebra::Vector2;
+
+use crate::{Body, GRAVITATIONAL_CONSTANT};
+
+/// The osculating orbital elements of a body relative to a primary, describing the conic section the body would follow
+/// if the two of them were alone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitalElements {
+    /// The semi-latus rectum, the distance from the primary perpendicular to the periapsis.
+    pub semi_latus_rectum: f64,
+    pub eccentricity: f64,
+    /// The angle of the periapsis from the x axis.
+    pub argument_of_periapsis: f64,
+    /// The angle of the body from the periapsis, in the direction of its motion.
+    pub true_anomaly: f64,
+    /// Whether the body moves counterclockwise around the primary.
+    pub counterclockwise: bool,
+}
+
+impl OrbitalElements {
+    /// Returns `None` if the body coincides with the primary or moves straight towards or away from it.
+    pub fn osculating(body: &Body, primary: &Body) -> Option<Self> {
+        let gravitational_parameter = GRAVITATIONAL_CONSTANT * (body.mass + primary.mass);
+        let position = body.position - primary.position;
+        let velocity = body.velocity - primary.velocity;
+        let distance = position.norm();
+        let angular_momentum = position.perp(&velocity);
+
+        if distance == 0f64 || angular_momentum == 0f64 || gravitational_parameter <= 0f64 {
+            return None;
+        }
+
+        let eccentricity_vector = ((velocity.norm_squared() - gravitational_parameter / distance) * position
+            - position.dot(&velocity) * velocity) / gravitational_parameter;
+        let eccentricity = eccentricity_vector.norm();
+        let direction = angular_momentum.signum();
+        let angle = position.y.atan2(position.x);
+
+        // A circular orbit has no periapsis, so it is put at the body.
+        let argument_of_periapsis = if eccentricity > f64::EPSILON {
+            eccentricity_vector.y.atan2(eccentricity_vector.x)
+        } else {
+            angle
+        };
+
+        Some(Self {
+            semi_latus_rectum: angular_momentum.powi(2) / gravitational_parameter,
+            eccentricity,
+            argument_of_periapsis,
+            true_anomaly: normalized_angle(direction * (angle - argument_of_periapsis)),
+            counterclockwise: direction > 0f64,
+        })
+    }
+
+    /// Returns the semi-major axis, negative for a hyperbola and infinite for a parabola.
+    pub fn semi_major_axis(&self) -> f64 {
+        self.semi_latus_rectum / (1f64 - self.eccentricity.powi(2))
+    }
+
+    pub fn is_bound(&self) -> bool {
+        self.eccentricity < 1f64
+    }
+
+    /// Returns the position relative to the primary at the true anomaly.
+    pub fn position(&self, true_anomaly: f64) -> Vector2<f64> {
+        let distance = self.semi_latus_rectum / (1f64 + self.eccentricity * true_anomaly.cos());
+        let direction = if self.counterclockwise { 1f64 } else { -1f64 };
+        let angle = self.argument_of_periapsis + direction * true_anomaly;
+        Vector2::new(angle.cos(), angle.sin()) * distance
+    }
+
+    /// Returns the velocity relative to the primary at the true anomaly, for the gravitational parameter G(m1 + m2).
+    pub fn velocity(&self, true_anomaly: f64, gravitational_parameter: f64) -> Vector2<f64> {
+        let speed_scale = (gravitational_parameter / self.semi_latus_rectum).sqrt();
+        let direction = if self.counterclockwise { 1f64 } else { -1f64 };
+        // the radial and the transverse components, rotated to the direction of the body
+        let angle = self.argument_of_periapsis + direction * true_anomaly;
+        let radial = speed_scale * self.eccentricity * true_anomaly.sin();
+        let transverse = direction * speed_scale * (1f64 + self.eccentricity * true_anomaly.cos());
+        Vector2::new(angle.cos(), angle.sin()) * radial + Vector2::new(-angle.sin(), angle.cos()) * transverse
+    }
+
+    /// Samples the conic relative to the primary with the number of points, the whole ellipse or the part of the
+    /// parabola or hyperbola not farther than the distance.
+    pub fn points(&self, count: usize, distance_max: f64) -> Vec<Vector2<f64>> {
+        let true_anomaly_max = if self.is_bound() {
+            PI
+        } else {
+            // where the distance is the maximum one, approaching the asymptote with the distance
+            let cosine = (self.semi_latus_rectum / distance_max - 1f64) / self.eccentricity;
+            if cosine > 1f64 {
+                return Vec::new();
+            }
+            cosine.max(-1f64).acos()
+        };
+
+        (0..=count)
+            .map(|index| -true_anomaly_max + 2f64 * true_anomaly_max * index as f64 / count as f64)
+            .map(|true_anomaly| self.position(true_anomaly))
+            .collect()
+    }
+}
+
+/// Returns the angle in the range (-π, π].
+fn normalized_angle(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(2f64 * PI);
+    if angle > PI {
+        angle - 2f64 * PI
+    } else {
+        angle
+    }
+}
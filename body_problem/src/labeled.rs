@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Body;
+
+/// Identifies a body regardless of its position among the others, which changes when another one is removed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BodyId(pub u64);
+
+impl BodyId {
+    /// Returns an id different from all the given ones.
+    pub fn unused<'a>(ids: impl IntoIterator<Item = &'a BodyId>) -> Self {
+        Self(ids.into_iter().map(|id| id.0).max().unwrap_or(0) + 1)
+    }
+}
+
+/// What a body is known by to the user.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BodyLabel {
+    pub id: BodyId,
+    pub name: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+impl BodyLabel {
+    pub fn new(id: BodyId, name: String, notes: Option<String>) -> Self {
+        Self { id, name, notes }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LabeledBody {
+    pub label: BodyLabel,
+    pub body: Body,
+}
+
+impl LabeledBody {
+    pub fn new(label: BodyLabel, body: Body) -> Self {
+        Self { label, body }
+    }
+}
@@ -1,9 +1,19 @@
+pub mod checkpoint;
+pub mod field;
+pub mod galaxy;
+pub mod generator;
+pub mod integrator;
+pub mod kepler;
+pub mod labeled;
+pub mod random;
+pub mod recorder;
+
 use std::cmp::max_by;
 
 use nalgebra::Vector2;
 use serde::{Deserialize, Serialize};
 
-const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-11;
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-11;
 const BODY_DISTANCE_MIN: f64 = 10f64;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -22,9 +32,37 @@ impl Body {
         -GRAVITATIONAL_CONSTANT * self.mass * other.mass / max_by((self.position - other.position).norm(), BODY_DISTANCE_MIN, |a: &f64, b: &f64| a.partial_cmp(b).unwrap())
     }
     
+    /// Returns the acceleration of this body caused by the gravity of the other one.
+    pub fn acceleration_towards(&self, other: &Self) -> Vector2<f64> {
+        GRAVITATIONAL_CONSTANT * other.mass * (other.position - self.position)
+            / max_by((other.position - self.position).norm(), BODY_DISTANCE_MIN, |a: &f64, b: &f64| a.partial_cmp(b).unwrap()).powi(3)
+    }
+
+    /// Returns the jerk, the time derivative of the acceleration towards the other body.
+    pub fn jerk_towards(&self, other: &Self) -> Vector2<f64> {
+        let position = other.position - self.position;
+        let velocity = other.velocity - self.velocity;
+        let distance = position.norm();
+        // The acceleration of the bodies closer than the minimum distance grows linearly with the distance.
+        if distance < BODY_DISTANCE_MIN {
+            return GRAVITATIONAL_CONSTANT * other.mass * velocity / BODY_DISTANCE_MIN.powi(3);
+        }
+        GRAVITATIONAL_CONSTANT * other.mass
+            * (velocity / distance.powi(3) - 3f64 * position.dot(&velocity) * position / distance.powi(5))
+    }
+
+    pub fn force_towards(&self, other: &Self) -> Vector2<f64> {
+        self.mass * self.acceleration_towards(other)
+    }
+
     pub fn kinetic_energy(&self) -> f64 {
         0.5 * self.mass * self.velocity.norm_squared()
     }
+
+    /// Returns the angular momentum about the origin (the z component, as the motion is planar).
+    pub fn angular_momentum(&self) -> f64 {
+        self.mass * self.position.perp(&self.velocity)
+    }
 }
 
 impl PartialEq for Body {
@@ -35,15 +73,47 @@ impl PartialEq for Body {
     }
 }
 
+/// Returns the potential energy of the whole system, counting each pair of bodies once.
+pub fn potential_energy(bodies: &[Body]) -> f64 {
+    bodies.iter().enumerate()
+        .flat_map(|(index, body1)| bodies[index + 1..].iter().map(move |body2| body1.potential_energy_to(body2)))
+        .sum()
+}
+
+pub fn kinetic_energy(bodies: &[Body]) -> f64 {
+    bodies.iter().map(Body::kinetic_energy).sum()
+}
+
+pub fn angular_momentum(bodies: &[Body]) -> f64 {
+    bodies.iter().map(Body::angular_momentum).sum()
+}
+
+/// Returns the net acceleration of each body caused by the gravity of all the others.
+pub fn accelerations(bodies: &[Body]) -> Vec<Vector2<f64>> {
+    bodies.iter().enumerate()
+        .map(|(index1, body1)| bodies.iter().enumerate()
+            .filter(|&(index2, _)| index1 != index2)
+            .map(|(_, body2)| body1.acceleration_towards(body2))
+            .sum())
+        .collect()
+}
+
+/// Returns the net acceleration and jerk of each body caused by the gravity of all the others.
+pub fn accelerations_and_jerks(bodies: &[Body]) -> Vec<(Vector2<f64>, Vector2<f64>)> {
+    bodies.iter().enumerate()
+        .map(|(index1, body1)| bodies.iter().enumerate()
+            .filter(|&(index2, _)| index1 != index2)
+            .fold((Vector2::zeros(), Vector2::zeros()), |(acceleration, jerk), (_, body2)| {
+                (acceleration + body1.acceleration_towards(body2), jerk + body1.jerk_towards(body2))
+            }))
+        .collect()
+}
+
 pub fn simulate(bodies: &[Body], step: f64) -> Vec<Body> {
     let mut bodies_new = bodies.to_vec();
 
-    for (index1, body1) in bodies_new.iter_mut().enumerate() {
-        body1.velocity += bodies.iter().enumerate()
-            .filter(|&(index2, _)| index1 != index2)
-            .map(|(_, body2)| GRAVITATIONAL_CONSTANT * body2.mass * (body2.position - body1.position)
-                / max_by((body2.position - body1.position).norm(), BODY_DISTANCE_MIN, |a: &f64, b: &f64| a.partial_cmp(b).unwrap()).powi(3))
-            .sum::<Vector2<f64>>() * step;
+    for (body, acceleration) in bodies_new.iter_mut().zip(accelerations(bodies)) {
+        body.velocity += acceleration * step;
     }
 
     for body in bodies_new.iter_mut() {
@@ -0,0 +1,41 @@
+use std::f64::consts::TAU;
+
+use nalgebra::Vector2;
+
+/// A small seeded pseudorandom number generator (SplitMix64), so that the generated bodies are the same for the same
+/// seed on every platform.
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a number uniformly distributed in [0, 1).
+    pub fn uniform(&mut self) -> f64 {
+        // the 53 bits of the mantissa
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number uniformly distributed in (0, 1], e.g. to take the logarithm of.
+    pub fn uniform_positive(&mut self) -> f64 {
+        1f64 - self.uniform()
+    }
+
+    /// Returns a unit vector in a uniformly distributed direction.
+    pub fn direction(&mut self) -> Vector2<f64> {
+        let angle = TAU * self.uniform();
+        Vector2::new(angle.cos(), angle.sin())
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::labeled::BodyLabel;
+use crate::Body;
+
+const BINARY_MAGIC: &[u8; 4] = b"BPTR";
+const BINARY_VERSION: u16 = 1;
+const UNITS: &str = "time [s], mass [kg], position [m], velocity [m/s]";
+
+/// The states of the bodies at a moment of the simulated time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sample {
+    /// The simulated time in seconds.
+    pub time: f64,
+    pub bodies: Vec<Body>,
+}
+
+/// Samples the states of the same bodies at a fixed cadence of the simulated time, to be written for analysis.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recorder {
+    /// The simulated time between the samples in seconds.
+    interval: f64,
+    samples: Vec<Sample>,
+}
+
+impl Recorder {
+    pub fn new(interval: f64) -> Self {
+        Self { interval, samples: Vec::new() }
+    }
+
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// Records the bodies if an interval has passed since the last sample, returning whether they were recorded.
+    pub fn record(&mut self, time: f64, bodies: &[Body]) -> bool {
+        // tolerating the rounding of the times summed from the steps
+        let due = self.samples.last()
+            .is_none_or(|last| time - last.time >= self.interval * (1f64 - 1e-9));
+        if due {
+            self.samples.push(Sample { time, bodies: bodies.to_vec() });
+        }
+        due
+    }
+
+    /// Drops the samples after the time, e.g. when the simulation goes back to it.
+    pub fn truncate(&mut self, time: f64) {
+        let length = self.samples.partition_point(|sample| sample.time <= time);
+        self.samples.truncate(length);
+    }
+
+    /// Writes the samples as CSV, one row per body and sample, preceded by comment lines describing the units and
+    /// the bodies, labeled in the same order.
+    pub fn write_csv(&self, labels: &[BodyLabel], mut writer: impl Write) -> io::Result<()> {
+        self.check_labels(labels)?;
+
+        writeln!(writer, "# body_problem trajectory")?;
+        writeln!(writer, "# units: {UNITS}")?;
+        writeln!(writer, "# interval: {}", self.interval)?;
+        writeln!(writer, "# bodies: id, name, mass")?;
+        for (label, mass) in labels.iter().zip(self.masses()) {
+            writeln!(writer, "# {}, {}, {mass}", label.id.0, csv_field(&label.name))?;
+        }
+
+        writeln!(writer, "time,body,x,y,vx,vy")?;
+        for sample in self.samples.iter() {
+            for (label, body) in labels.iter().zip(sample.bodies.iter()) {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    sample.time, label.id.0, body.position.x, body.position.y, body.velocity.x, body.velocity.y,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the samples in a compact little-endian binary format: the magic `BPTR`, the version (u16), the units
+    /// (u32 length and UTF-8), the interval (f64), the number of bodies (u32) each followed by its id (u64), name
+    /// (u32 length and UTF-8) and mass (f64), the number of samples (u64) and each sample, the time (f64) followed by
+    /// the position and the velocity of each body (4 f64).
+    pub fn write_binary(&self, labels: &[BodyLabel], mut writer: impl Write) -> io::Result<()> {
+        self.check_labels(labels)?;
+
+        writer.write_all(BINARY_MAGIC)?;
+        writer.write_all(&BINARY_VERSION.to_le_bytes())?;
+        write_string(&mut writer, UNITS)?;
+        writer.write_all(&self.interval.to_le_bytes())?;
+
+        writer.write_all(&(labels.len() as u32).to_le_bytes())?;
+        for (label, mass) in labels.iter().zip(self.masses()) {
+            writer.write_all(&label.id.0.to_le_bytes())?;
+            write_string(&mut writer, &label.name)?;
+            writer.write_all(&mass.to_le_bytes())?;
+        }
+
+        writer.write_all(&(self.samples.len() as u64).to_le_bytes())?;
+        for sample in self.samples.iter() {
+            writer.write_all(&sample.time.to_le_bytes())?;
+            for body in sample.bodies.iter() {
+                for value in [body.position.x, body.position.y, body.velocity.x, body.velocity.y] {
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn masses(&self) -> Vec<f64> {
+        self.samples.first()
+            .map(|sample| sample.bodies.iter().map(|body| body.mass).collect())
+            .unwrap_or_default()
+    }
+
+    fn check_labels(&self, labels: &[BodyLabel]) -> io::Result<()> {
+        match self.samples.iter().find(|sample| sample.bodies.len() != labels.len()) {
+            Some(sample) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} labels given for {} bodies at {} s", labels.len(), sample.bodies.len(), sample.time),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The format the recorded samples are written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordingFormat {
+    Csv,
+    Binary,
+}
+
+impl RecordingFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Binary => "bptr",
+        }
+    }
+
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Binary => "application/octet-stream",
+        }
+    }
+
+    pub fn write(&self, recorder: &Recorder, labels: &[BodyLabel], writer: impl Write) -> io::Result<()> {
+        match self {
+            Self::Csv => recorder.write_csv(labels, writer),
+            Self::Binary => recorder.write_binary(labels, writer),
+        }
+    }
+}
+
+fn write_string(writer: &mut impl Write, text: &str) -> io::Result<()> {
+    writer.write_all(&(text.len() as u32).to_le_bytes())?;
+    writer.write_all(text.as_bytes())
+}
+
+/// Escapes the control characters, which could end the comment line the field is in, and the backslashes, then quotes
+/// the field if it contains a separator or a quote.
+fn csv_field(text: &str) -> String {
+    let text = text.chars()
+        .map(|character| if character.is_control() || character == '\\' {
+            character.escape_default().to_string()
+        } else {
+            character.to_string()
+        })
+        .collect::<String>();
+    if text.contains([',', '"']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::ErrorKind;
+
+use body_problem::checkpoint::{Run, RunConfig};
+use body_problem::integrator::Integrator;
+use body_problem::Body;
+use nalgebra::Vector2;
+
+fn bodies() -> Vec<Body> {
+    vec![
+        Body::new(5.97e24, Vector2::new(0f64, 0f64), Vector2::new(0f64, -12.3)),
+        Body::new(7.35e22, Vector2::new(3.84e8, 0f64), Vector2::new(0f64, 1022f64)),
+        Body::new(1e20, Vector2::new(-2e8, 1.5e8), Vector2::new(800f64, 600f64)),
+    ]
+}
+
+fn assert_bit_identical(run1: &Run, run2: &Run) {
+    assert_eq!(run1.step_count(), run2.step_count());
+    assert_eq!(run1.time().to_bits(), run2.time().to_bits());
+    assert_eq!(run1.bodies().len(), run2.bodies().len());
+    for (body1, body2) in run1.bodies().iter().zip(run2.bodies()) {
+        let values = |body: &Body| [body.mass, body.position.x, body.position.y, body.velocity.x, body.velocity.y]
+            .map(f64::to_bits);
+        assert_eq!(values(body1), values(body2));
+    }
+}
+
+#[test]
+fn split_run_equals_uninterrupted_one() {
+    for integrator in Integrator::ALL {
+        let config = RunConfig::new(integrator, 60f64, Some(42));
+
+        let mut run_uninterrupted = Run::new(bodies(), config, 1000f64);
+        run_uninterrupted.advance(1000);
+
+        let mut run_first = Run::new(bodies(), config, 1000f64);
+        run_first.advance(377);
+        let mut checkpoint = Vec::new();
+        run_first.write_checkpoint(&mut checkpoint).unwrap();
+        drop(run_first);
+
+        let mut run_resumed = Run::read_checkpoint(checkpoint.as_slice()).unwrap();
+        assert_eq!(run_resumed.config(), &config);
+        run_resumed.advance(623);
+
+        assert_bit_identical(&run_resumed, &run_uninterrupted);
+    }
+}
+
+#[test]
+fn checkpoint_saved_to_disk_resumes() {
+    let config = RunConfig::new(Integrator::VelocityVerlet, 10f64, None);
+    let mut run = Run::new(bodies(), config, 0f64);
+    run.advance(50);
+
+    let path = std::env::temp_dir().join(format!("body_problem_checkpoint_{}.bpck", std::process::id()));
+    run.save(&path).unwrap();
+    let mut run_loaded = Run::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(run_loaded, run);
+
+    run.advance(50);
+    run_loaded.advance(50);
+    assert_bit_identical(&run_loaded, &run);
+}
+
+#[test]
+fn invalid_checkpoints_are_rejected() {
+    let run = Run::new(bodies(), RunConfig::new(Integrator::RungeKutta4, 1f64, None), 0f64);
+    let mut checkpoint = Vec::new();
+    run.write_checkpoint(&mut checkpoint).unwrap();
+
+    let mut wrong_magic = checkpoint.clone();
+    wrong_magic[0] = b'X';
+    assert_eq!(Run::read_checkpoint(wrong_magic.as_slice()).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let truncated = &checkpoint[..checkpoint.len() - 1];
+    assert_eq!(Run::read_checkpoint(truncated).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,86 @@
+use body_problem::galaxy::{DiscGalaxy, GalaxyCollision, Spin};
+use body_problem::{Body, GRAVITATIONAL_CONSTANT};
+use nalgebra::Vector2;
+
+const MASS: f64 = 1e30;
+const RADIUS: f64 = 1e10;
+
+fn collision(eccentricity: f64, spins: [Spin; 2]) -> GalaxyCollision {
+    GalaxyCollision::new(
+        [DiscGalaxy::new(MASS, RADIUS, 120, spins[0]), DiscGalaxy::new(0.5 * MASS, 0.5 * RADIUS, 60, spins[1])],
+        2f64 * RADIUS,
+        eccentricity,
+        10f64 * RADIUS,
+    )
+}
+
+#[test]
+fn collision_has_the_bodies_of_both_galaxies() {
+    let bodies = collision(1f64, [Spin::Prograde; 2]).bodies();
+
+    assert_eq!(bodies.len(), 1 + 120 + 1 + 60);
+    assert_eq!(bodies[0].mass, MASS);
+    assert_eq!(bodies[121].mass, 0.5 * MASS);
+    assert!(bodies.iter().enumerate().all(|(index, body)| index == 0 || index == 121 || body.mass == 0f64));
+}
+
+#[test]
+fn collision_is_in_the_centre_of_mass_frame() {
+    let bodies = collision(1f64, [Spin::Prograde; 2]).bodies();
+
+    let position = bodies.iter().map(|body| body.mass * body.position).sum::<Vector2<f64>>();
+    let momentum = bodies.iter().map(|body| body.mass * body.velocity).sum::<Vector2<f64>>();
+    assert!(position.norm() <= 1e-12 * MASS * RADIUS, "{position}");
+    assert!(momentum.norm() <= 1e-12 * MASS * bodies[0].velocity.norm(), "{momentum}");
+}
+
+#[test]
+fn galaxies_approach_on_the_orbit() {
+    for eccentricity in [0.5, 1f64, 1.5] {
+        let bodies = collision(eccentricity, [Spin::Prograde; 2]).bodies();
+        let position = bodies[121].position - bodies[0].position;
+        let velocity = bodies[121].velocity - bodies[0].velocity;
+        let gravitational_parameter = GRAVITATIONAL_CONSTANT * 1.5 * MASS;
+
+        // Bound orbits start at most at the apocentre.
+        let distance_max = if eccentricity < 1f64 {
+            2f64 * RADIUS * (1f64 + eccentricity) / (1f64 - eccentricity)
+        } else {
+            f64::INFINITY
+        };
+        let distance = position.norm();
+        assert!((distance - (10f64 * RADIUS).min(distance_max)).abs() <= 1e-9 * RADIUS, "distance {distance:e}");
+        assert!(position.dot(&velocity) <= 0f64, "eccentricity {eccentricity}");
+
+        // the pericentre distance from the angular momentum and the energy
+        let angular_momentum = position.perp(&velocity);
+        let energy = velocity.norm_squared() / 2f64 - gravitational_parameter / position.norm();
+        let eccentricity_orbit = (1f64 + 2f64 * energy * angular_momentum.powi(2) / gravitational_parameter.powi(2))
+            .max(0f64)
+            .sqrt();
+        let pericentre_distance = angular_momentum.powi(2) / gravitational_parameter / (1f64 + eccentricity_orbit);
+        assert!(angular_momentum > 0f64);
+        assert!((eccentricity_orbit - eccentricity).abs() <= 1e-6,
+            "eccentricity {eccentricity_orbit} instead of {eccentricity}");
+        assert!((pericentre_distance - 2f64 * RADIUS).abs() <= 1e-6 * RADIUS, "pericentre {pericentre_distance:e}");
+    }
+}
+
+#[test]
+fn particles_circle_their_centres_in_the_sense_of_the_spin() {
+    let bodies = collision(1f64, [Spin::Prograde, Spin::Retrograde]).bodies();
+
+    let particles = |central: &Body, particles: &[Body], radius: f64, sense: f64| {
+        for particle in particles {
+            let (position, velocity) = (particle.position - central.position, particle.velocity - central.velocity);
+            let distance = position.norm();
+            assert!(distance > 0f64 && distance <= radius * (1f64 + 1e-9), "distance {distance:e}");
+
+            let speed_circular = (GRAVITATIONAL_CONSTANT * central.mass / distance).sqrt();
+            assert!((velocity.norm() - speed_circular).abs() <= 1e-9 * speed_circular);
+            assert!(sense * position.perp(&velocity) > 0f64);
+        }
+    };
+    particles(&bodies[0], &bodies[1..121], RADIUS, 1f64);
+    particles(&bodies[121], &bodies[122..], 0.5 * RADIUS, -1f64);
+}
@@ -0,0 +1,89 @@
+use body_problem::generator::{Distribution, Generator};
+use body_problem::random::Random;
+use body_problem::{kinetic_energy, potential_energy, Body, GRAVITATIONAL_CONSTANT};
+use nalgebra::Vector2;
+
+const MASS_TOTAL: f64 = 1e30;
+const RADIUS_SCALE: f64 = 1e10;
+
+fn generate(distribution: Distribution, count: usize, seed: u64) -> Vec<Body> {
+    Generator::new(distribution, count, MASS_TOTAL, RADIUS_SCALE, seed).generate()
+}
+
+#[test]
+fn random_numbers_are_the_same_for_the_same_seed() {
+    // the first output of SplitMix64 seeded with 0
+    assert_eq!(Random::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+
+    let numbers = |seed: u64| {
+        let mut random = Random::new(seed);
+        (0..100).map(|_| random.uniform()).collect::<Vec<_>>()
+    };
+    assert_eq!(numbers(42), numbers(42));
+    assert_ne!(numbers(42), numbers(43));
+    assert!(numbers(42).iter().all(|number| (0f64..1f64).contains(number)));
+}
+
+#[test]
+fn bodies_are_the_same_for_the_same_seed() {
+    for distribution in Distribution::ALL {
+        assert_eq!(generate(distribution, 50, 42), generate(distribution, 50, 42), "{}", distribution.name());
+        assert_ne!(generate(distribution, 50, 42), generate(distribution, 50, 43), "{}", distribution.name());
+    }
+}
+
+#[test]
+fn bodies_have_the_count_and_the_total_mass() {
+    for distribution in Distribution::ALL {
+        for count in [0, 1, 2, 100] {
+            let bodies = generate(distribution, count, 1);
+
+            assert_eq!(bodies.len(), count, "{}", distribution.name());
+            if count > 0 {
+                let mass = bodies.iter().map(|body| body.mass).sum::<f64>();
+                assert!((mass - MASS_TOTAL).abs() <= 1e-12 * MASS_TOTAL, "{}: mass {mass:e}", distribution.name());
+            }
+        }
+    }
+}
+
+#[test]
+fn bodies_are_in_the_centre_of_mass_frame() {
+    for distribution in Distribution::ALL {
+        let bodies = generate(distribution, 100, 2);
+        let speed_scale = (GRAVITATIONAL_CONSTANT * MASS_TOTAL / RADIUS_SCALE).sqrt();
+
+        let position = bodies.iter().map(|body| body.mass * body.position).sum::<Vector2<f64>>();
+        let momentum = bodies.iter().map(|body| body.mass * body.velocity).sum::<Vector2<f64>>();
+        assert!(position.norm() <= 1e-9 * MASS_TOTAL * RADIUS_SCALE, "{}: {position}", distribution.name());
+        assert!(momentum.norm() <= 1e-9 * MASS_TOTAL * speed_scale, "{}: {momentum}", distribution.name());
+    }
+}
+
+#[test]
+fn keplerian_ring_orbits_the_central_body_near_the_radius() {
+    let bodies = generate(Distribution::KeplerianRing, 100, 3);
+    let central = &bodies[0];
+    assert!(bodies[1..].iter().all(|body| body.mass < central.mass));
+
+    for body in bodies[1..].iter() {
+        let (position, velocity) = (body.position - central.position, body.velocity - central.velocity);
+        let radius = position.norm();
+        assert!((0.9 * RADIUS_SCALE..=1.1 * RADIUS_SCALE).contains(&radius), "radius {radius:e}");
+
+        let speed_circular = (GRAVITATIONAL_CONSTANT * central.mass / radius).sqrt();
+        assert!((velocity.norm() - speed_circular).abs() <= 1e-9 * speed_circular,
+            "speed {} instead of {speed_circular}", velocity.norm());
+        // counterclockwise and perpendicular to the radius
+        assert!(position.perp(&velocity) > 0f64);
+        assert!(position.dot(&velocity).abs() <= 1e-9 * radius * speed_circular);
+    }
+}
+
+#[test]
+fn plummer_sphere_is_in_virial_equilibrium_in_the_plane() {
+    let bodies = generate(Distribution::PlummerSphere, 500, 7);
+
+    let virial_ratio = 2f64 * kinetic_energy(&bodies) / -potential_energy(&bodies);
+    assert!((virial_ratio - 1f64).abs() < 1e-9, "virial ratio {virial_ratio}");
+}
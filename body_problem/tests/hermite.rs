@@ -0,0 +1,109 @@
+use body_problem::integrator::{Integrator, IntegratorState};
+use body_problem::{accelerations_and_jerks, kinetic_energy, potential_energy, simulate, Body, GRAVITATIONAL_CONSTANT};
+use nalgebra::Vector2;
+
+// the units of the Pythagorean problem, given in the units where G = 1, far larger than the minimum distance of the
+// bodies
+const LENGTH_UNIT: f64 = 1e9;
+const TIME_UNIT: f64 = 1f64;
+const MASS_UNIT: f64 = LENGTH_UNIT * LENGTH_UNIT * LENGTH_UNIT / (GRAVITATIONAL_CONSTANT * TIME_UNIT * TIME_UNIT);
+
+/// The Pythagorean three-body problem of Burrau: the masses 3, 4 and 5 at rest at the vertices of a right triangle
+/// with the sides 3, 4 and 5, each opposite the side of its length.
+fn pythagorean_bodies() -> Vec<Body> {
+    [(3f64, 1f64, 3f64), (4f64, -2f64, -1f64), (5f64, 1f64, -1f64)]
+        .iter()
+        .map(|&(mass, x, y)| Body::new(mass * MASS_UNIT, Vector2::new(x, y) * LENGTH_UNIT, Vector2::zeros()))
+        .collect()
+}
+
+fn energy(bodies: &[Body]) -> f64 {
+    kinetic_energy(bodies) + potential_energy(bodies)
+}
+
+fn energy_error(mut integrate: impl FnMut(&[Body], f64) -> Vec<Body>, step: f64, duration: f64) -> f64 {
+    let bodies_start = pythagorean_bodies();
+    let mut bodies = bodies_start.clone();
+    for _ in 0..(duration / step).round() as usize {
+        bodies = integrate(&bodies, step);
+    }
+    ((energy(&bodies) - energy(&bodies_start)) / energy(&bodies_start)).abs()
+}
+
+#[test]
+fn hermite_conserves_energy_better_than_simulate_on_the_pythagorean_problem() {
+    // through the first close encounters
+    let duration = 10f64 * TIME_UNIT;
+    let step = 1e-3 * TIME_UNIT;
+
+    let error_simulate = energy_error(simulate, step, duration);
+    let error_hermite = energy_error(|bodies, step| Integrator::Hermite.integrate(bodies, step), step, duration);
+
+    assert!(error_hermite < 1e-3, "relative energy error of the Hermite method {error_hermite:e}");
+    assert!(error_hermite * 1e3 < error_simulate,
+        "relative energy errors of the Hermite method {error_hermite:e} and of simulate {error_simulate:e}");
+}
+
+#[test]
+fn hermite_covers_steps_longer_than_its_substeps() {
+    // A single step is divided into the substeps, so that it stays accurate however long the step is.
+    let error_short = energy_error(|bodies, step| Integrator::Hermite.integrate(bodies, step), 1e-3 * TIME_UNIT, TIME_UNIT);
+    let error_long = energy_error(|bodies, step| Integrator::Hermite.integrate(bodies, step), TIME_UNIT, TIME_UNIT);
+
+    assert!(error_short < 1e-6, "relative energy error with the short steps {error_short:e}");
+    assert!(error_long < 1e-5, "relative energy error with the long step {error_long:e}");
+}
+
+#[test]
+fn hermite_continues_with_the_substep_of_the_previous_step() {
+    let duration = 10f64 * TIME_UNIT;
+    let step = 1e-3 * TIME_UNIT;
+    let mut state = IntegratorState::default();
+    let mut substeps = 0;
+
+    let error = energy_error(|bodies, step| {
+        let (bodies, substeps_step) = Integrator::Hermite.integrate_continuing(bodies, step, &mut state);
+        substeps += substeps_step;
+        bodies
+    }, step, duration);
+
+    assert!(error < 1e-3, "relative energy error of the Hermite method {error:e}");
+    // The substeps shorten during the close encounters only.
+    let steps = (duration / step).round() as u64;
+    assert!(substeps < 2 * steps, "{substeps} substeps in {steps} steps");
+    assert_ne!(state, IntegratorState::default());
+}
+
+#[test]
+fn jerks_are_the_derivatives_of_the_accelerations() {
+    let bodies = pythagorean_bodies().into_iter()
+        .zip([Vector2::new(0.3, -0.1), Vector2::new(-0.2, 0.4), Vector2::new(0.1, 0.2)])
+        .map(|(body, velocity)| Body::new(body.mass, body.position, velocity * LENGTH_UNIT / TIME_UNIT))
+        .collect::<Vec<_>>();
+    let dt = 1e-6 * TIME_UNIT;
+    let moved = |dt: f64| bodies.iter()
+        .map(|body| Body::new(body.mass, body.position + body.velocity * dt, body.velocity))
+        .collect::<Vec<_>>();
+
+    let accelerations_after = accelerations_and_jerks(&moved(dt));
+    let accelerations_before = accelerations_and_jerks(&moved(-dt));
+    for (index, (_, jerk)) in accelerations_and_jerks(&bodies).into_iter().enumerate() {
+        let jerk_estimated = (accelerations_after[index].0 - accelerations_before[index].0) / (2f64 * dt);
+        assert!((jerk - jerk_estimated).norm() <= 1e-6 * jerk.norm(), "jerk {jerk} estimated as {jerk_estimated}");
+    }
+}
+
+#[test]
+fn hermite_finishes_a_step_of_nearly_coincident_bodies() {
+    // The substeps would shrink without a bound as the bodies pass through each other.
+    let bodies = [
+        Body::new(MASS_UNIT, Vector2::zeros(), Vector2::zeros()),
+        Body::new(MASS_UNIT, Vector2::new(1e-9, 0f64), Vector2::zeros()),
+    ];
+
+    let (bodies, substeps) = Integrator::Hermite
+        .integrate_continuing(&bodies, TIME_UNIT, &mut IntegratorState::default());
+
+    assert!(substeps <= 10_000, "{substeps} substeps");
+    assert!(bodies.iter().all(|body| body.position.iter().chain(body.velocity.iter()).all(|value| value.is_finite())));
+}
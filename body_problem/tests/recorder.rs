@@ -0,0 +1,158 @@
+use body_problem::integrator::Integrator;
+use body_problem::labeled::{BodyId, BodyLabel};
+use body_problem::recorder::Recorder;
+use body_problem::Body;
+use nalgebra::Vector2;
+
+fn bodies(count: usize) -> Vec<Body> {
+    (0..count)
+        .map(|index| Body::new(1f64, Vector2::new(index as f64, 0f64), Vector2::zeros()))
+        .collect()
+}
+
+fn label(id: u64, name: &str) -> BodyLabel {
+    BodyLabel::new(BodyId(id), name.to_string(), None)
+}
+
+/// Records the Earth and the Moon every step of a minute over an hour, sampling every 10 minutes.
+fn recorded_run() -> Recorder {
+    let mut bodies = vec![
+        Body::new(5.97e24, Vector2::zeros(), Vector2::new(0f64, -12.3)),
+        Body::new(7.35e22, Vector2::new(3.84e8, 0f64), Vector2::new(0f64, 1022f64)),
+    ];
+    let mut recorder = Recorder::new(600f64);
+    recorder.record(0f64, &bodies);
+    for step in 1..=60 {
+        bodies = Integrator::VelocityVerlet.integrate(&bodies, 60f64);
+        recorder.record(step as f64 * 60f64, &bodies);
+    }
+    recorder
+}
+
+fn read<const N: usize>(data: &mut &[u8]) -> [u8; N] {
+    let (bytes, rest) = data.split_at(N);
+    *data = rest;
+    bytes.try_into().unwrap()
+}
+
+fn read_f64(data: &mut &[u8]) -> f64 {
+    f64::from_le_bytes(read(data))
+}
+
+fn read_string(data: &mut &[u8]) -> String {
+    let length = u32::from_le_bytes(read(data)) as usize;
+    let (bytes, rest) = data.split_at(length);
+    *data = rest;
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[test]
+fn samples_are_recorded_at_the_interval() {
+    let mut recorder = Recorder::new(1f64);
+    // the times summed from steps of 0.1, which fall short of the whole seconds by the rounding
+    let mut time = 0f64;
+    let mut recorded = Vec::new();
+    for _ in 0..=35 {
+        if recorder.record(time, &bodies(1)) {
+            recorded.push(time);
+        }
+        time += 0.1;
+    }
+
+    assert_eq!(recorded.len(), 4, "{recorded:?}");
+    assert!(recorded.iter().enumerate().all(|(index, time)| (time - index as f64).abs() < 1e-9), "{recorded:?}");
+    assert_eq!(recorder.samples().len(), 4);
+
+    recorder.truncate(1.5);
+    assert_eq!(recorder.samples().len(), 2);
+    assert!(!recorder.record(1.9, &bodies(1)));
+    assert!(recorder.record(2f64, &bodies(1)));
+}
+
+#[test]
+fn recorded_run_is_written_as_csv() {
+    let recorder = recorded_run();
+    assert_eq!(recorder.samples().len(), 7);
+
+    let mut data = Vec::new();
+    recorder.write_csv(&[label(3, "Earth"), label(8, "Moon")], &mut data).unwrap();
+    let csv = String::from_utf8(data).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines[..7], [
+        "# body_problem trajectory",
+        "# units: time [s], mass [kg], position [m], velocity [m/s]",
+        "# interval: 600",
+        "# bodies: id, name, mass",
+        "# 3, Earth, 5970000000000000000000000",
+        "# 8, Moon, 73500000000000000000000",
+        "time,body,x,y,vx,vy",
+    ]);
+    assert_eq!(lines.len(), 7 + 2 * 7);
+    for (sample, rows) in recorder.samples().iter().zip(lines[7..].chunks(2)) {
+        for ((id, body), row) in [3, 8].iter().zip(sample.bodies.iter()).zip(rows) {
+            let values = row.split(',').map(|value| value.parse::<f64>().unwrap()).collect::<Vec<_>>();
+            assert_eq!(values, [
+                sample.time, *id as f64, body.position.x, body.position.y, body.velocity.x, body.velocity.y,
+            ]);
+        }
+    }
+    assert!(lines[7].starts_with("0,3,0,0,0,-12.3"), "{}", lines[7]);
+    assert!(lines.last().unwrap().starts_with("3600,8,"), "{}", lines.last().unwrap());
+}
+
+#[test]
+fn recorded_run_is_written_in_the_binary_layout() {
+    let recorder = recorded_run();
+
+    let mut data = Vec::new();
+    recorder.write_binary(&[label(3, "Earth"), label(8, "Moon")], &mut data).unwrap();
+    let mut data = data.as_slice();
+
+    assert_eq!(&read::<4>(&mut data), b"BPTR");
+    assert_eq!(u16::from_le_bytes(read(&mut data)), 1);
+    assert_eq!(read_string(&mut data), "time [s], mass [kg], position [m], velocity [m/s]");
+    assert_eq!(read_f64(&mut data), 600f64);
+
+    assert_eq!(u32::from_le_bytes(read(&mut data)), 2);
+    for (id, name, mass) in [(3, "Earth", 5.97e24), (8, "Moon", 7.35e22)] {
+        assert_eq!(u64::from_le_bytes(read(&mut data)), id);
+        assert_eq!(read_string(&mut data), name);
+        assert_eq!(read_f64(&mut data), mass);
+    }
+
+    assert_eq!(u64::from_le_bytes(read(&mut data)), 7);
+    for sample in recorder.samples() {
+        assert_eq!(read_f64(&mut data).to_bits(), sample.time.to_bits());
+        for body in sample.bodies.iter() {
+            for value in [body.position.x, body.position.y, body.velocity.x, body.velocity.y] {
+                assert_eq!(read_f64(&mut data).to_bits(), value.to_bits());
+            }
+        }
+    }
+    assert!(data.is_empty(), "{} bytes left", data.len());
+}
+
+#[test]
+fn samples_of_other_bodies_than_labeled_are_rejected() {
+    let mut recorder = Recorder::new(1f64);
+    recorder.record(0f64, &bodies(2));
+    recorder.record(1f64, &bodies(3));
+
+    let labels = [label(0, "a"), label(1, "b")];
+    assert!(recorder.write_csv(&labels, Vec::new()).is_err());
+    assert!(recorder.write_binary(&labels, Vec::new()).is_err());
+}
+
+#[test]
+fn names_cannot_break_the_csv_header() {
+    let mut recorder = Recorder::new(1f64);
+    recorder.record(0f64, &bodies(1));
+
+    let mut data = Vec::new();
+    recorder.write_csv(&[label(0, "first\nline, \"second\"")], &mut data).unwrap();
+
+    let csv = String::from_utf8(data).unwrap();
+    assert!(csv.lines().any(|line| line == r#"# 0, "first\nline, ""second""", 1"#), "{csv}");
+    assert!(csv.lines().take_while(|line| !line.starts_with("time")).all(|line| line.starts_with('#')), "{csv}");
+}
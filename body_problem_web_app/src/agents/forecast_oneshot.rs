@@ -0,0 +1,52 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+use yew_agent::prelude::oneshot;
+
+use body_problem::Body;
+use body_problem::integrator::{Integrator, IntegratorState};
+
+const FORECAST_MAX_SEGMENT_LENGTH: f64 = 2f64;
+/// The most steps simulated, cutting the forecast short if its duration takes more, e.g. with a very short step.
+const FORECAST_STEPS_MAX: u64 = 1_000_000;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ForecastInput {
+    pub(crate) bodies: Vec<Body>,
+    pub(crate) duration: Duration,
+    pub(crate) integrator: Integrator,
+    pub(crate) step: f64,
+}
+
+impl ForecastInput {
+    pub fn new(bodies: Vec<Body>, duration: Duration, integrator: Integrator, step: f64) -> Self {
+        Self { bodies, duration, integrator, step }
+    }
+}
+
+/// Simulates the bodies on a copy of them, returning the path of each of them.
+#[oneshot(ForecastOneshot)]
+pub async fn forecast_oneshot(input: ForecastInput) -> Vec<Vec<Vector2<f64>>> {
+    let mut bodies = input.bodies;
+    let mut paths = bodies.iter().map(|body| vec![body.position]).collect::<Vec<_>>();
+
+    let steps = ((input.duration.as_secs_f64() / input.step) as u64).min(FORECAST_STEPS_MAX);
+    let mut integrator_state = IntegratorState::default();
+    for _ in 0..steps {
+        (bodies, _) = input.integrator.integrate_continuing(bodies.as_ref(), input.step, &mut integrator_state);
+
+        // Points are recorded only once a body moves far enough to keep the paths short.
+        if bodies.iter().zip(paths.iter()).any(|(body, path)|
+            (body.position - path.last().unwrap()).norm() > FORECAST_MAX_SEGMENT_LENGTH) {
+            for (body, path) in bodies.iter().zip(paths.iter_mut()) {
+                path.push(body.position);
+            }
+        }
+    }
+
+    for (body, path) in bodies.iter().zip(paths.iter_mut()) {
+        path.push(body.position);
+    }
+
+    paths
+}
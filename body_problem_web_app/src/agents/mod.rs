@@ -1 +1,2 @@
+pub mod forecast_oneshot;
 pub mod simulation_reactor;
@@ -1,27 +1,228 @@
 use futures::{FutureExt, SinkExt, StreamExt};
-use futures::stream::FusedStream;
 use web_time::{Duration, Instant};
 use yew_agent::prelude::{reactor, ReactorScope};
 
-use body_problem::{Body, simulate};
+use body_problem::{kinetic_energy, potential_energy, Body};
+use body_problem::integrator::{Integrator, IntegratorState};
+use body_problem::labeled::BodyLabel;
+use body_problem::recorder::{Recorder, RecordingFormat};
+use nalgebra::Vector2;
 use serde::{Deserialize, Serialize};
 
-const TARGET_FPS: f64 = 50f64;
-const STEP: f64 = 0.0001;
+use crate::models::trajectory_segment::TrajectorySegment;
+
+pub(crate) const TARGET_FPS_DEFAULT: f64 = 50f64;
+pub(crate) const STEP_DEFAULT: f64 = 0.0001;
+/// The shortest step, below which the simulation could not keep up with any speed.
+pub(crate) const STEP_MIN: f64 = 1e-6;
+/// The most substeps taken in a frame by the integrator, one per step unless it divides them, so that a frame cannot
+/// take so long that the agent stops responding.
+const SUBSTEPS_PER_FRAME_MAX: u64 = 20_000;
+// 1 s of the simulated time at the default step
+const CHECKPOINT_INTERVAL_STEPS: u64 = 10_000;
+/// The most checkpoints kept, beyond which every other one is dropped and the interval doubled, so that a long run
+/// takes a bounded memory at the cost of re-simulating more steps when seeking.
+const CHECKPOINTS_MAX: usize = 64;
+/// The simulated time between the samples recorded for the export in seconds.
+const RECORDING_INTERVAL: f64 = 0.01;
+/// The most states of single bodies recorded, about 20 MB, after which the recording stops.
+const RECORDING_BODY_SAMPLES_MAX: usize = 500_000;
+/// How many states each output carries, the intermediate ones being used for smoother trajectories.
+const STATES_PER_OUTPUT: u64 = 4;
+// the mass, the position and the velocity
+const PACKED_BODY_LENGTH: usize = 5;
+// the position and the velocity, as the mass does not change in a batch
+const PACKED_BODY_KINEMATICS_LENGTH: usize = 4;
 
 #[derive(Clone, Serialize, Deserialize)]
-pub struct SimulationReactorInstruction {
-    state: Option<SimulationState>,
-    time_to_reality_ratio: f64,
+pub enum SimulationCommand {
+    /// Starts running a new simulation from the state.
+    Start(SimulationState),
+    Pause,
+    Resume,
+    /// Replaces the state without changing whether the simulation runs, keeping the timeline if the state belongs
+    /// to it.
+    SetState(SimulationState),
+    /// Applies the edits made to a state of the timeline, which starts a new one.
+    Edit(SimulationEdits),
+    /// Moves to the time of the timeline.
+    Seek(Duration),
+    /// Sets the ratio of the simulated time to the real time.
+    SetSpeed(f64),
+    /// Sets the integrator, which starts a new timeline at the current state.
+    SetIntegrator(Integrator),
+    /// Sets the simulated time of a single step, which starts a new timeline at the current state.
+    SetStep(f64),
+    /// Sets how many times per second the states are sent while running.
+    SetTargetFps(f64),
+    /// Advances the paused simulation by the number of steps, at most as many as in a frame.
+    Step(u64),
+    /// Requests the current state and the diagnostics.
+    RequestSnapshot,
+    /// Starts recording samples for the export, continuing the samples kept up to the current state if any.
+    StartRecording,
+    StopRecording,
+    /// Requests the samples recorded up to the current state of the timeline, written in the format with the bodies
+    /// labeled in order.
+    ExportRecording(RecordingFormat, Vec<BodyLabel>),
 }
 
-impl SimulationReactorInstruction {
-    pub fn new(state: Option<SimulationState>, time_to_reality_ratio: f64) -> Self {
-        Self { state, time_to_reality_ratio }
+impl SimulationCommand {
+    /// Whether the command replaces the state, making the states sent before it outdated.
+    pub fn replaces_state(&self) -> bool {
+        matches!(self, Self::Start(_) | Self::SetState(_) | Self::Edit(_) | Self::Seek(_))
     }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+pub enum SimulationResponse {
+    /// The states passed since the last ones, sent each frame while running and in response to the commands
+    /// changing the state.
+    State(SimulationReactorOutput),
+    /// The simulation was paused at the state, with the revision like the states.
+    Paused { state: SimulationState, revision: u64 },
+    /// The command could not be carried out.
+    Error(String),
+    Diagnostics(SimulationDiagnostics),
+    Recording(RecordingFormat, Vec<u8>),
+    /// The recording changed, sent whenever it does.
+    RecordingStatus(RecordingStatus),
+}
+
+/// What last happened to the recording.
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RecordingChange {
+    Started,
+    #[default]
+    Stopped,
+    /// The recording stopped as it reached the most samples kept.
+    Full,
+    /// The samples were dropped as the bodies were replaced or edited, the recording going on from the new state.
+    Reset,
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct RecordingStatus {
+    pub(crate) recording: bool,
+    /// Whether any samples are kept for the export.
+    pub(crate) recorded: bool,
+    pub(crate) change: RecordingChange,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationDiagnostics {
+    pub(crate) integrator: Integrator,
+    pub(crate) step: f64,
+    pub(crate) target_fps: f64,
+    pub(crate) time_to_reality_ratio: f64,
+    pub(crate) running: bool,
+    /// The change of the total energy since the start of the timeline, relative to the initial one.
+    pub(crate) energy_error: f64,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum BodyEdit {
+    Set { index: usize, body: PackedBodies },
+    Add { body: PackedBodies },
+    Remove { index: usize },
+}
+
+/// Changes of the bodies made at a moment of the current timeline, sent instead of the whole edited state.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationEdits {
+    /// The time of the timeline the edits were made at.
+    base: Duration,
+    edits: Vec<BodyEdit>,
+    duration_elapsed_total: Duration,
+}
+
+impl SimulationEdits {
+    /// Returns the edits turning the bodies of the base into those of the edited state, if they are expressible as
+    /// edits of single bodies, an addition or a removal.
+    pub fn between(base: &SimulationState, edited: &SimulationState) -> Option<Self> {
+        let (bodies_base, bodies_edited) = (&base.bodies, &edited.bodies);
+
+        let edits = if bodies_base.len() == bodies_edited.len() {
+            bodies_base.iter().zip(bodies_edited.iter()).enumerate()
+                .filter(|(_, (body_base, body_edited))| body_base != body_edited)
+                .map(|(index, (_, body_edited))| BodyEdit::Set {
+                    index,
+                    body: PackedBodies::from(std::slice::from_ref(body_edited)),
+                })
+                .collect()
+        } else if bodies_base.len() + 1 == bodies_edited.len() && bodies_edited.starts_with(bodies_base) {
+            vec![BodyEdit::Add { body: PackedBodies::from(&bodies_edited[bodies_base.len()..]) }]
+        } else if bodies_base.len() == bodies_edited.len() + 1 {
+            let index = (0..bodies_base.len()).find(|&index| {
+                bodies_base[..index] == bodies_edited[..index] && bodies_base[index + 1..] == bodies_edited[index..]
+            })?;
+            vec![BodyEdit::Remove { index }]
+        } else {
+            return None;
+        };
+
+        Some(Self { base: base.duration_elapsed_total, edits, duration_elapsed_total: edited.duration_elapsed_total })
+    }
+
+    fn apply(&self, bodies: &mut Vec<Body>) {
+        for edit in self.edits.iter() {
+            match edit {
+                BodyEdit::Set { index, body } => {
+                    if let (Some(body_old), Some(body)) = (bodies.get_mut(*index), body.unpack().pop()) {
+                        *body_old = body;
+                    }
+                }
+                BodyEdit::Add { body } => bodies.extend(body.unpack()),
+                BodyEdit::Remove { index } => {
+                    if *index < bodies.len() {
+                        bodies.remove(*index);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bodies packed into a flat array of their masses, positions and velocities, which is compact when encoded.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackedBodies(Vec<f64>);
+
+impl PackedBodies {
+    pub fn unpack(&self) -> Vec<Body> {
+        self.0.chunks_exact(PACKED_BODY_LENGTH)
+            .map(|values| Body::new(values[0], Vector2::new(values[1], values[2]), Vector2::new(values[3], values[4])))
+            .collect()
+    }
+}
+
+impl From<&[Body]> for PackedBodies {
+    fn from(bodies: &[Body]) -> Self {
+        Self(bodies.iter()
+            .flat_map(|body| [body.mass, body.position.x, body.position.y, body.velocity.x, body.velocity.y])
+            .collect())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct PackedSimulationState {
+    bodies: PackedBodies,
+    duration_elapsed_total: Duration,
+}
+
+impl From<SimulationState> for PackedSimulationState {
+    fn from(state: SimulationState) -> Self {
+        Self { bodies: PackedBodies::from(state.bodies.as_slice()), duration_elapsed_total: state.duration_elapsed_total }
+    }
+}
+
+impl From<PackedSimulationState> for SimulationState {
+    fn from(state: PackedSimulationState) -> Self {
+        Self::new(state.bodies.unpack(), state.duration_elapsed_total)
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "PackedSimulationState", from = "PackedSimulationState")]
 pub struct SimulationState {
     pub(crate) bodies: Vec<Body>,
     pub(crate) duration_elapsed_total: Duration,
@@ -33,74 +234,679 @@ impl SimulationState {
     }
 }
 
-#[reactor(SimulationReactor)]
-pub async fn simulation_reactor(
-    /*
-    - None => Stop the simulation.
-    - Some => Set the bodies (if Some) and the speed (always).
-    */
-    mut scope: ReactorScope<Option<SimulationReactorInstruction>, Option<SimulationState>>
-) {
-    let mut state = None;
-    let mut steps_per_result = ((1f64 / TARGET_FPS) / STEP) as u64;
+/// Consecutive states of the same bodies, packed into flat arrays. The masses are stored only once.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SimulationStateBatch {
+    masses: Vec<f64>,
+    durations_elapsed_total: Vec<Duration>,
+    kinematics: Vec<f64>,
+}
 
-    loop {
-        if scope.is_terminated() {
+impl SimulationStateBatch {
+    fn new(states: &[SimulationState]) -> Self {
+        Self {
+            masses: states.first()
+                .map(|state| state.bodies.iter().map(|body| body.mass).collect())
+                .unwrap_or_default(),
+            durations_elapsed_total: states.iter().map(|state| state.duration_elapsed_total).collect(),
+            kinematics: states.iter()
+                .flat_map(|state| state.bodies.iter())
+                .flat_map(|body| [body.position.x, body.position.y, body.velocity.x, body.velocity.y])
+                .collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.durations_elapsed_total.len()
+    }
+
+    pub fn state(&self, index: usize) -> SimulationState {
+        let length = self.masses.len() * PACKED_BODY_KINEMATICS_LENGTH;
+        SimulationState::new(
+            self.kinematics[index * length..(index + 1) * length]
+                .chunks_exact(PACKED_BODY_KINEMATICS_LENGTH)
+                .zip(self.masses.iter())
+                .map(|(values, &mass)| Body::new(mass, Vector2::new(values[0], values[1]), Vector2::new(values[2], values[3])))
+                .collect(),
+            self.durations_elapsed_total[index],
+        )
+    }
+
+    /// Returns the latest state, i.e. the current one.
+    pub fn last(&self) -> SimulationState {
+        self.state(self.len() - 1)
+    }
+
+    /// Returns the trajectory segments of the states preceding the latest one.
+    pub(crate) fn segments_intermediate(&self) -> Vec<TrajectorySegment> {
+        (0..self.len().saturating_sub(1))
+            .map(|index| {
+                let state = self.state(index);
+                TrajectorySegment::new(&state.bodies, state.duration_elapsed_total)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SimulationReactorOutput {
+    pub(crate) states: SimulationStateBatch,
+    /// How far the current timeline has been simulated, i.e. the latest time that can be sought to.
+    pub(crate) duration_recorded: Duration,
+    /// The number of the commands replacing the state received before the states, which tells the outdated states
+    /// apart.
+    pub(crate) revision: u64,
+    /// How the previous frame went, if the simulation was running during it.
+    pub(crate) statistics: Option<FrameStatistics>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameStatistics {
+    pub(crate) steps: u64,
+    /// How long computing the steps took.
+    pub(crate) compute_duration: Duration,
+    /// The real time between the starts of the frame and the next one.
+    pub(crate) frame_duration: Duration,
+    pub(crate) duration_simulated: Duration,
+    pub(crate) speed_requested: f64,
+}
+
+impl FrameStatistics {
+    /// Returns the ratio of the simulated time to the real time actually reached.
+    pub fn speed_achieved(&self) -> f64 {
+        self.duration_simulated.as_secs_f64() / self.frame_duration.as_secs_f64()
+    }
+}
+
+/// Periodic checkpoints of a simulation run, from which any of its earlier moments can be re-simulated.
+struct SimulationTimeline {
+    /// The step indices with the bodies and the state of the integrator at them, in an ascending order.
+    checkpoints: Vec<(u64, Vec<Body>, IntegratorState)>,
+    /// The steps between the checkpoints, growing as they are thinned.
+    checkpoint_interval: u64,
+    step_index_max: u64,
+    /// The time the step indices count from.
+    duration_start: Duration,
+    integrator: Integrator,
+    step: f64,
+}
+
+impl SimulationTimeline {
+    fn new(state: &SimulationState, integrator: Integrator, step: f64) -> Self {
+        Self {
+            checkpoints: vec![(0, state.bodies.clone(), IntegratorState::default())],
+            checkpoint_interval: CHECKPOINT_INTERVAL_STEPS,
+            step_index_max: 0,
+            duration_start: state.duration_elapsed_total,
+            integrator,
+            step,
+        }
+    }
+
+    fn record(&mut self, step_index: u64, bodies: &[Body], integrator_state: IntegratorState) {
+        self.step_index_max = self.step_index_max.max(step_index);
+
+        let (step_index_last, _, _) = self.checkpoints.last().unwrap();
+        if step_index >= step_index_last + self.checkpoint_interval {
+            self.checkpoints.push((step_index, bodies.to_vec(), integrator_state));
+        }
+
+        if self.checkpoints.len() > CHECKPOINTS_MAX {
+            // The first checkpoint is kept as it is where the timeline starts.
+            let mut index = 0;
+            self.checkpoints.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+            self.checkpoint_interval *= 2;
+        }
+    }
+
+    fn reconstruct(&self, step_index: u64) -> (Vec<Body>, IntegratorState) {
+        let (step_index_checkpoint, bodies, integrator_state) = self.checkpoints.iter()
+            .rev()
+            .find(|(step_index_checkpoint, _, _)| *step_index_checkpoint <= step_index)
+            .unwrap();
+
+        let (mut bodies, mut integrator_state) = (bodies.clone(), *integrator_state);
+        for _ in *step_index_checkpoint..step_index {
+            (bodies, _) = self.integrator.integrate_continuing(bodies.as_ref(), self.step, &mut integrator_state);
+        }
+        (bodies, integrator_state)
+    }
+
+    /// Returns the state of the integrator the timeline continues with from the bodies if they are what it would reach
+    /// at the time, i.e. they were not edited.
+    fn continuation(&self, state: &SimulationState) -> Option<IntegratorState> {
+        let step_index = self.step_index(state.duration_elapsed_total)
+            .filter(|&step_index| step_index <= self.step_index_max)?;
+        let (bodies, integrator_state) = self.reconstruct(step_index);
+        (bodies == state.bodies).then_some(integrator_state)
+    }
+
+    /// Returns the index of the step closest to the time, unless it precedes the timeline.
+    fn step_index(&self, duration: Duration) -> Option<u64> {
+        let duration = duration.checked_sub(self.duration_start)?;
+        Some((duration.as_secs_f64() / self.step).round() as u64)
+    }
+
+    fn duration(&self, step_index: u64) -> Duration {
+        self.duration_start + Duration::from_secs_f64(step_index as f64 * self.step)
+    }
+
+    fn duration_recorded(&self) -> Duration {
+        self.duration(self.step_index_max)
+    }
+}
+
+fn total_energy(bodies: &[Body]) -> f64 {
+    kinetic_energy(bodies) + potential_energy(bodies)
+}
+
+struct SimulationRun {
+    state: SimulationState,
+    integrator_state: IntegratorState,
+    step_index: u64,
+    timeline: SimulationTimeline,
+    /// The states passed since the last output.
+    states_intermediate: Vec<SimulationState>,
+    energy_initial: f64,
+}
+
+impl SimulationRun {
+    fn new(state: SimulationState, integrator: Integrator, step: f64) -> Self {
+        let timeline = SimulationTimeline::new(&state, integrator, step);
+        let energy_initial = total_energy(&state.bodies);
+        Self {
+            state,
+            integrator_state: IntegratorState::default(),
+            step_index: 0,
+            timeline,
+            states_intermediate: Vec::new(),
+            energy_initial,
+        }
+    }
+
+    /// Replaces the state, keeping the checkpoints if the state belongs to the same timeline, returning whether it
+    /// does.
+    fn set_state(&mut self, state: SimulationState) -> bool {
+        if let Some(integrator_state) = self.timeline.continuation(&state) {
+            self.step_index = self.timeline.step_index(state.duration_elapsed_total).unwrap();
+            self.state = state;
+            self.integrator_state = integrator_state;
+            self.states_intermediate.clear();
+            true
+        } else {
+            *self = Self::new(state, self.timeline.integrator, self.timeline.step);
+            false
+        }
+    }
+
+    fn set_integration(&mut self, integrator: Integrator, step: f64) {
+        if integrator != self.timeline.integrator || step != self.timeline.step {
+            *self = Self::new(self.state.clone(), integrator, step);
+        }
+    }
+
+    /// Applies the edits made at a moment of the timeline, which starts a new one.
+    fn edit(&mut self, edits: &SimulationEdits) -> Result<(), String> {
+        let step_index = self.timeline.step_index(edits.base)
+            .ok_or_else(|| format!("the edited state at {:.2} s is not in the timeline", edits.base.as_secs_f64()))?;
+        let (mut bodies, _) = self.timeline.reconstruct(step_index);
+        edits.apply(&mut bodies);
+        *self = Self::new(
+            SimulationState::new(bodies, edits.duration_elapsed_total),
+            self.timeline.integrator,
+            self.timeline.step,
+        );
+        Ok(())
+    }
+
+    fn seek(&mut self, duration: Duration) -> Result<(), String> {
+        let step_index = self.timeline.step_index(duration)
+            .ok_or_else(|| format!("cannot seek to {:.2} s, before the start of the timeline", duration.as_secs_f64()))?;
+        if step_index > self.timeline.step_index_max {
+            return Err(format!(
+                "cannot seek to {:.2} s, only {:.2} s has been simulated",
+                duration.as_secs_f64(),
+                self.timeline.duration_recorded().as_secs_f64(),
+            ));
+        }
+
+        let (bodies, integrator_state) = self.timeline.reconstruct(step_index);
+        self.state = SimulationState::new(bodies, self.timeline.duration(step_index));
+        self.integrator_state = integrator_state;
+        self.step_index = step_index;
+        self.states_intermediate.clear();
+        Ok(())
+    }
+
+    /// Advances by the steps, or fewer once the integrator has taken the most substeps, returning the steps taken.
+    fn advance(&mut self, steps: u64, substeps_max: u64, recording: &mut Recording) -> u64 {
+        let mut substeps = 0;
+        let mut steps_taken = 0;
+        for step in 1..=steps {
+            if substeps >= substeps_max {
+                break;
+            }
+            let (bodies, substeps_step) = self.timeline.integrator
+                .integrate_continuing(self.state.bodies.as_ref(), self.timeline.step, &mut self.integrator_state);
+            self.state.bodies = bodies;
+            substeps += substeps_step;
+            steps_taken = step;
+            recording.record(self.timeline.duration(self.step_index + step).as_secs_f64(), &self.state.bodies);
+
+            if step < steps && step % (steps / STATES_PER_OUTPUT).max(1) == 0 {
+                self.states_intermediate.push(SimulationState::new(
+                    self.state.bodies.clone(),
+                    self.timeline.duration(self.step_index + step),
+                ));
+            }
+        }
+        self.step_index += steps_taken;
+        self.state.duration_elapsed_total = self.timeline.duration(self.step_index);
+        self.timeline.record(self.step_index, &self.state.bodies, self.integrator_state);
+        steps_taken
+    }
+
+    /// Returns the states passed since the last output, ending with the current one.
+    fn take_output(&mut self, revision: u64) -> SimulationReactorOutput {
+        let mut states = std::mem::take(&mut self.states_intermediate);
+        states.push(self.state.clone());
+        SimulationReactorOutput {
+            states: SimulationStateBatch::new(&states),
+            duration_recorded: self.timeline.duration_recorded(),
+            revision,
+            statistics: None,
+        }
+    }
+
+    fn energy_error(&self) -> f64 {
+        (total_energy(&self.state.bodies) - self.energy_initial) / self.energy_initial.abs()
+    }
+}
+
+/// The samples recorded for the export while the user asks for them, kept across the runs of the same bodies.
+struct Recording {
+    recorder: Recorder,
+    active: bool,
+    change: RecordingChange,
+    /// The number of the states of single bodies in the samples, bounded by [`RECORDING_BODY_SAMPLES_MAX`].
+    body_samples: usize,
+}
+
+impl Recording {
+    fn new() -> Self {
+        Self { recorder: Recorder::new(RECORDING_INTERVAL), active: false, change: RecordingChange::Stopped, body_samples: 0 }
+    }
+
+    fn status(&self) -> RecordingStatus {
+        RecordingStatus { recording: self.active, recorded: self.body_samples > 0, change: self.change }
+    }
+
+    /// Starts recording at the state, after the samples kept up to it.
+    fn start(&mut self, state: &SimulationState) {
+        self.truncate(state.duration_elapsed_total);
+        self.active = true;
+        self.change = RecordingChange::Started;
+        self.record(state.duration_elapsed_total.as_secs_f64(), &state.bodies);
+    }
+
+    fn stop(&mut self) {
+        if self.active {
+            self.active = false;
+            self.change = RecordingChange::Stopped;
+        }
+    }
+
+    fn record(&mut self, time: f64, bodies: &[Body]) {
+        if !self.active || !self.recorder.record(time, bodies) {
             return;
         }
+        self.body_samples += bodies.len();
+        if self.body_samples >= RECORDING_BODY_SAMPLES_MAX {
+            self.active = false;
+            self.change = RecordingChange::Full;
+        }
+    }
+
+    /// Drops the samples after the time, when the simulation goes back to it.
+    fn truncate(&mut self, duration: Duration) {
+        self.recorder.truncate(duration.as_secs_f64());
+        self.body_samples = self.recorder.samples().iter().map(|sample| sample.bodies.len()).sum();
+    }
+
+    /// Drops all the samples as the bodies were replaced, recording on from the new state if active.
+    fn reset(&mut self, state: &SimulationState) {
+        if self.body_samples > 0 {
+            self.recorder = Recorder::new(RECORDING_INTERVAL);
+            self.body_samples = 0;
+            self.change = RecordingChange::Reset;
+        }
+        self.record(state.duration_elapsed_total.as_secs_f64(), &state.bodies);
+    }
+}
+
+/// What the reactor keeps between the received commands.
+struct SimulationReactorState {
+    run: Option<SimulationRun>,
+    recording: Recording,
+    running: bool,
+    time_to_reality_ratio: f64,
+    integrator: Integrator,
+    step: f64,
+    target_fps: f64,
+    /// The fraction of a step owed from the previous frames, so that any speed is kept exactly on average.
+    steps_owed: f64,
+    revision: u64,
+}
+
+impl SimulationReactorState {
+    fn new() -> Self {
+        Self {
+            run: None,
+            recording: Recording::new(),
+            running: false,
+            time_to_reality_ratio: 1f64,
+            integrator: Integrator::default(),
+            step: STEP_DEFAULT,
+            target_fps: TARGET_FPS_DEFAULT,
+            steps_owed: 0f64,
+            revision: 0,
+        }
+    }
+
+    fn frame_duration(&self) -> Duration {
+        Duration::from_secs_f64(1f64 / self.target_fps)
+    }
 
-        if let Some(instruction) = scope.next().await {
-            if let Some(instruction) = instruction {
-                state = instruction.state;
-                steps_per_result = (((1f64 / TARGET_FPS) / STEP) * instruction.time_to_reality_ratio) as u64;
+    /// Returns the whole number of steps to advance by in this frame, carrying the remainder over to the next one.
+    fn steps_for_frame(&mut self) -> u64 {
+        self.steps_owed += self.time_to_reality_ratio * self.frame_duration().as_secs_f64() / self.step;
+        let steps = self.steps_owed.floor();
+        self.steps_owed -= steps;
+        steps as u64
+    }
+
+    /// Carries out the command, returning the responses to it.
+    fn apply(&mut self, command: SimulationCommand) -> Vec<SimulationResponse> {
+        if command.replaces_state() {
+            self.revision += 1;
+        }
+
+        let recording_status = self.recording.status();
+        let mut responses = match self.apply_to_run(command) {
+            Ok(responses) => responses,
+            Err(message) => vec![SimulationResponse::Error(message)],
+        };
+        if self.recording.status() != recording_status {
+            responses.push(SimulationResponse::RecordingStatus(self.recording.status()));
+        }
+        responses
+    }
+
+    fn apply_to_run(&mut self, command: SimulationCommand) -> Result<Vec<SimulationResponse>, String> {
+        let revision = self.revision;
+
+        match command {
+            SimulationCommand::Start(state) => {
+                let mut run = SimulationRun::new(state, self.integrator, self.step);
+                self.recording.reset(&run.state);
+                let output = run.take_output(revision);
+                self.run = Some(run);
+                self.running = true;
+                return Ok(vec![SimulationResponse::State(output)]);
             }
-            break;
+            SimulationCommand::SetSpeed(time_to_reality_ratio) => {
+                if !time_to_reality_ratio.is_finite() || time_to_reality_ratio < 0f64 {
+                    return Err(format!("invalid simulation speed {time_to_reality_ratio}"));
+                }
+                self.time_to_reality_ratio = time_to_reality_ratio;
+                return Ok(Vec::new());
+            }
+            SimulationCommand::SetIntegrator(integrator) => {
+                self.integrator = integrator;
+                if let Some(run) = self.run.as_mut() {
+                    run.set_integration(self.integrator, self.step);
+                }
+                return Ok(Vec::new());
+            }
+            SimulationCommand::SetStep(step) => {
+                if !step.is_finite() || step < STEP_MIN {
+                    return Err(format!("invalid step {step}"));
+                }
+                self.step = step;
+                if let Some(run) = self.run.as_mut() {
+                    run.set_integration(self.integrator, self.step);
+                }
+                return Ok(Vec::new());
+            }
+            SimulationCommand::SetTargetFps(target_fps) => {
+                if !target_fps.is_finite() || target_fps <= 0f64 {
+                    return Err(format!("invalid target frame rate {target_fps}"));
+                }
+                self.target_fps = target_fps;
+                return Ok(Vec::new());
+            }
+            _ => {}
         }
+
+        let run = self.run.as_mut().ok_or("no simulation has been started")?;
+        Ok(match command {
+            SimulationCommand::Pause => {
+                self.running = false;
+                vec![SimulationResponse::Paused { state: run.state.clone(), revision }]
+            }
+            SimulationCommand::Resume => {
+                self.running = true;
+                Vec::new()
+            }
+            SimulationCommand::SetState(state) => {
+                if run.set_state(state) {
+                    self.recording.truncate(run.state.duration_elapsed_total);
+                } else {
+                    self.recording.reset(&run.state);
+                }
+                vec![SimulationResponse::State(run.take_output(revision))]
+            }
+            SimulationCommand::Edit(edits) => {
+                run.edit(&edits)?;
+                self.recording.reset(&run.state);
+                vec![SimulationResponse::State(run.take_output(revision))]
+            }
+            SimulationCommand::Seek(duration) => {
+                run.seek(duration)?;
+                self.recording.truncate(run.state.duration_elapsed_total);
+                vec![SimulationResponse::State(run.take_output(revision))]
+            }
+            SimulationCommand::Step(steps) => {
+                if self.running {
+                    return Err("cannot step a running simulation".to_string());
+                }
+                run.advance(steps, SUBSTEPS_PER_FRAME_MAX, &mut self.recording);
+                vec![SimulationResponse::State(run.take_output(revision))]
+            }
+            SimulationCommand::RequestSnapshot => vec![
+                SimulationResponse::State(run.take_output(revision)),
+                SimulationResponse::Diagnostics(SimulationDiagnostics {
+                    integrator: self.integrator,
+                    step: self.step,
+                    target_fps: self.target_fps,
+                    time_to_reality_ratio: self.time_to_reality_ratio,
+                    running: self.running,
+                    energy_error: run.energy_error(),
+                }),
+            ],
+            SimulationCommand::StartRecording => {
+                self.recording.start(&run.state);
+                Vec::new()
+            }
+            SimulationCommand::StopRecording => {
+                self.recording.stop();
+                Vec::new()
+            }
+            SimulationCommand::ExportRecording(format, labels) => {
+                let mut data = Vec::new();
+                format.write(&self.recording.recorder, &labels, &mut data)
+                    .map_err(|error| format!("failed to export the recording: {error}"))?;
+                vec![SimulationResponse::Recording(format, data)]
+            }
+            SimulationCommand::Start(_) | SimulationCommand::SetSpeed(_) | SimulationCommand::SetIntegrator(_)
+            | SimulationCommand::SetStep(_) | SimulationCommand::SetTargetFps(_) => unreachable!(),
+        })
     }
+}
 
-    let mut none_sent = false;
+#[reactor(SimulationReactor)]
+pub async fn simulation_reactor(mut scope: ReactorScope<SimulationCommand, SimulationResponse>) {
+    let mut reactor_state = SimulationReactorState::new();
     let mut taken_duration = Duration::ZERO;
+    // when the previous frame started and its statistics, apart from its duration known only once it ends
+    let mut frame_last: Option<(Instant, FrameStatistics)> = None;
 
     loop {
         // For some reason, it is required to always sleep for some time in order for the agent to work.
-        async_std::task::sleep(Duration::from_millis((1000f64 / TARGET_FPS) as u64).checked_sub(taken_duration).unwrap_or(Duration::from_nanos(1))).await;
+        async_std::task::sleep(reactor_state.frame_duration().checked_sub(taken_duration).unwrap_or(Duration::from_nanos(1))).await;
 
         let start = Instant::now();
 
-        if let Some(Some(instruction)) = futures::future::poll_fn(|cx| scope.poll_next_unpin(cx)).now_or_never() {
-            match instruction {
-                Some(instruction) => {
-                    if instruction.state.is_some() {
-                        state = instruction.state;
-                    }
-                    steps_per_result = (((1f64 / TARGET_FPS) / STEP) * instruction.time_to_reality_ratio) as u64;
-                }
-                None => {
-                    state = None
-                }
+        // All the commands received since the last frame are carried out in order.
+        let mut responses = Vec::new();
+        loop {
+            match futures::future::poll_fn(|cx| scope.poll_next_unpin(cx)).now_or_never() {
+                Some(Some(command)) => responses.extend(reactor_state.apply(command)),
+                Some(None) => return,
+                None => break,
+            }
+        }
+        for response in responses {
+            if scope.send(response).await.is_err() {
+                return;
             }
         }
 
-        if state.is_none() {
-            if !none_sent && scope.send(None).await.is_err() {
-                break;
+        if reactor_state.running {
+            let revision = reactor_state.revision;
+            let (step, speed_requested) = (reactor_state.step, reactor_state.time_to_reality_ratio);
+            let steps = reactor_state.steps_for_frame();
+            let run = reactor_state.run.as_mut().unwrap();
+
+            let mut output = run.take_output(revision);
+            output.statistics = frame_last.take().map(|(frame_start, statistics)| FrameStatistics {
+                frame_duration: start - frame_start,
+                ..statistics
+            });
+            if scope.send(SimulationResponse::State(output)).await.is_err() {
+                return;
             }
 
-            none_sent = true;
-            continue;
+            let recording_status = reactor_state.recording.status();
+            let compute_start = Instant::now();
+            let steps_taken = run.advance(steps, SUBSTEPS_PER_FRAME_MAX, &mut reactor_state.recording);
+            if steps_taken < steps {
+                // The simulation falls behind the speed instead of catching up later, which would only take longer.
+                reactor_state.steps_owed = 0f64;
+            }
+            frame_last = Some((start, FrameStatistics {
+                steps: steps_taken,
+                compute_duration: compute_start.elapsed(),
+                frame_duration: Duration::ZERO,
+                duration_simulated: Duration::from_secs_f64(steps_taken as f64 * step),
+                speed_requested,
+            }));
+            if reactor_state.recording.status() != recording_status
+                && scope.send(SimulationResponse::RecordingStatus(reactor_state.recording.status())).await.is_err() {
+                return;
+            }
+        } else {
+            frame_last = None;
         }
-        none_sent = false;
 
-        if scope.send(state.clone()).await.is_err() {
-            break;
+        taken_duration = start.elapsed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoints_are_thinned_to_the_maximum() {
+        // the mass of the body tells the step it was recorded at
+        let body = |step_index: u64| vec![Body::new(step_index as f64, Vector2::zeros(), Vector2::zeros())];
+        let state = SimulationState::new(body(0), Duration::ZERO);
+        let mut timeline = SimulationTimeline::new(&state, Integrator::SemiImplicitEuler, 0.01);
+
+        let step_index_max = 50 * CHECKPOINTS_MAX as u64 * CHECKPOINT_INTERVAL_STEPS;
+        for step_index in (1_000..=step_index_max).step_by(1_000) {
+            timeline.record(step_index, &body(step_index), IntegratorState::default());
+            assert!(timeline.checkpoints.len() <= CHECKPOINTS_MAX, "{} checkpoints", timeline.checkpoints.len());
         }
 
-        let state = state.as_mut().unwrap();
-        for _ in 0..steps_per_result {
-            state.bodies = simulate(state.bodies.as_ref(), STEP);
+        assert!(timeline.checkpoints.len() > CHECKPOINTS_MAX / 2);
+        assert_eq!(timeline.checkpoints[0].0, 0);
+        assert!(timeline.checkpoints.windows(2).all(|pair| pair[1].0 - pair[0].0 <= timeline.checkpoint_interval));
+        assert!(step_index_max - timeline.checkpoints.last().unwrap().0 < timeline.checkpoint_interval);
+        assert!(timeline.checkpoints.iter().all(|(step_index, bodies, _)| bodies == &body(*step_index)));
+        assert_eq!(timeline.step_index_max, step_index_max);
+    }
+
+    #[test]
+    fn recording_stops_once_full() {
+        let bodies = vec![Body::new(1f64, Vector2::zeros(), Vector2::zeros()); 10_000];
+        let mut recording = Recording::new();
+        recording.start(&SimulationState::new(bodies.clone(), Duration::ZERO));
+
+        let samples_max = RECORDING_BODY_SAMPLES_MAX / bodies.len();
+        for sample in 1..2 * samples_max {
+            recording.record(sample as f64 * RECORDING_INTERVAL, &bodies);
         }
-        state.duration_elapsed_total += Duration::from_secs_f64(STEP * steps_per_result as f64);
 
-        taken_duration = start.elapsed();
+        assert_eq!(recording.recorder.samples().len(), samples_max);
+        assert_eq!(recording.body_samples, RECORDING_BODY_SAMPLES_MAX);
+        assert!(recording.status() == RecordingStatus { recording: false, recorded: true, change: RecordingChange::Full });
+
+        recording.start(&SimulationState::new(bodies, Duration::ZERO));
+        assert_eq!(recording.recorder.samples().len(), 1);
+        assert!(recording.status().change == RecordingChange::Started);
+    }
+
+    /// The Pythagorean three-body problem, whose close encounters shorten the substeps of the Hermite method.
+    fn pythagorean_state() -> SimulationState {
+        let bodies = [(3f64, 1f64, 3f64), (4f64, -2f64, -1f64), (5f64, 1f64, -1f64)]
+            .iter()
+            .map(|&(mass, x, y)| Body::new(mass * 1e12, Vector2::new(x, y) * 10f64, Vector2::zeros()))
+            .collect();
+        SimulationState::new(bodies, Duration::ZERO)
+    }
+
+    #[test]
+    fn advancing_stops_at_the_most_substeps() {
+        let mut run = SimulationRun::new(pythagorean_state(), Integrator::Hermite, 1f64);
+
+        let steps = run.advance(1_000, 100, &mut Recording::new());
+
+        assert!((1..1_000).contains(&steps), "{steps} steps");
+        assert_eq!(run.step_index, steps);
+        assert_eq!(run.state.duration_elapsed_total, run.timeline.duration(steps));
+    }
+
+    #[test]
+    fn seeking_continues_as_the_run_did() {
+        let mut run = SimulationRun::new(pythagorean_state(), Integrator::Hermite, 5e-2);
+        let mut recording = Recording::new();
+        run.advance(12_345, u64::MAX, &mut recording);
+        let state = run.state.clone();
+        run.advance(1_000, u64::MAX, &mut recording);
+        let state_later = run.state.clone();
+
+        run.seek(state.duration_elapsed_total).unwrap();
+        assert_eq!(run.state.bodies, state.bodies);
+        run.advance(1_000, u64::MAX, &mut recording);
+        assert_eq!(run.state.bodies, state_later.bodies);
+
+        assert!(run.set_state(state));
+        run.advance(1_000, u64::MAX, &mut recording);
+        assert_eq!(run.state.bodies, state_later.bodies);
     }
 }
@@ -0,0 +1,9 @@
+use yew_agent::Registrable;
+
+use body_problem_web_app::agents::forecast_oneshot::ForecastOneshot;
+
+fn main() {
+    wasm_logger::init(wasm_logger::Config::default());
+    
+    ForecastOneshot::registrar().register();
+}
@@ -1,5 +1,7 @@
 use yew::prelude::*;
+use yew_agent::oneshot::OneshotProvider;
 use yew_agent::reactor::ReactorProvider;
+use crate::agents::forecast_oneshot::ForecastOneshot;
 use crate::agents::simulation_reactor::SimulationReactor;
 use crate::components::footer::Footer;
 use crate::components::simulation_panel::SimulationPanel;
@@ -11,10 +13,12 @@ pub fn app() -> Html {
     
     html! {
         <ReactorProvider<SimulationReactor> path="/simulation_reactor.js">
+        <OneshotProvider<ForecastOneshot> path="/forecast_oneshot.js">
         <main>
             <SimulationPanel/>
             <Footer/>
         </main>
+        </OneshotProvider<ForecastOneshot>>
         </ReactorProvider<SimulationReactor>>
     }
 }
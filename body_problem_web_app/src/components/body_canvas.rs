@@ -1,15 +1,41 @@
 use std::f64::consts::PI;
 
-use web_sys::HtmlCanvasElement;
-use yew::{function_component, Html, html, Properties, use_context, use_effect_with, use_node_ref, use_state};
+use nalgebra::Vector2;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, MouseEvent};
+use yew::{function_component, Callback, Html, html, Properties, use_context, use_effect_with, use_node_ref, use_state};
+
+use body_problem::{accelerations, Body};
+use body_problem::labeled::BodyId;
 
 use crate::models::rendered_body::RenderedBody;
 use crate::models::settings::Settings;
+use crate::models::unit_system::Quantity;
 use crate::utils::{CanvasClear, SimulationCanvasInitialize};
 
+const VELOCITY_ARROW_COLOR: &str = "#50c878";
+const ACCELERATION_ARROW_COLOR: &str = "#e05050";
+const ARROW_HEAD_LENGTH: f64 = 6f64;
+const LABEL_COLOR: &str = "#d4d4d4";
+const LABEL_FONT: &str = "12px sans-serif";
+// the distance of a label from the circle of its body
+const LABEL_OFFSET: f64 = 4f64;
+const SELECTION_COLOR: &str = "#ffffff";
+// the gap between the circle of the selected body and the ring around it
+const SELECTION_RING_GAP: f64 = 3f64;
+// Small bodies are hit within this distance of their centre, so that they can be hovered over at all.
+const HIT_RADIUS_MIN: f64 = 6f64;
+// the offset of the tooltip from the pointer
+const TOOLTIP_OFFSET: f64 = 12f64;
+
 #[derive(Properties, PartialEq)]
 pub struct BodyCanvasProps {
     pub(crate) rendered_bodies: Vec<RenderedBody>,
+    /// Whether to draw the bodies themselves, not just the overlays.
+    #[prop_or(true)]
+    pub(crate) bodies_shown: bool,
+    pub(crate) selected_body: Option<BodyId>,
+    /// Called with the clicked body, or `None` when clicked beside the bodies.
+    pub(crate) select_callback: Callback<Option<BodyId>>,
 }
 
 #[function_component(BodyCanvas)]
@@ -18,6 +44,8 @@ pub fn body_canvas(props: &BodyCanvasProps) -> Html {
     let canvas = canvas_ref.cast::<HtmlCanvasElement>();
     let context = use_state(|| None);
     let settings = use_context::<Settings>().unwrap();
+    // the hovered body and the pointer position relative to the canvas
+    let hovered = use_state(|| None::<(BodyId, Vector2<f64>)>);
 
     {
         let context = context.clone();
@@ -39,22 +67,173 @@ pub fn body_canvas(props: &BodyCanvasProps) -> Html {
     if let (Some(context), Some(_)) = ((*context).clone(), canvas) {
         context.clear().unwrap();
 
+        let vector_overlays = settings.vector_overlays;
+        let bodies = props.rendered_bodies.iter()
+            .map(|rendered_body| rendered_body.body.clone())
+            .collect::<Vec<Body>>();
+
+        if vector_overlays.force_lines_shown {
+            draw_force_lines(&context, &bodies);
+        }
+
         // reversing for a more intuitive layer order
-        for rendered_body in props.rendered_bodies.iter().rev() {
+        for rendered_body in props.rendered_bodies.iter().rev().filter(|_| props.bodies_shown) {
             context.set_fill_style(&rendered_body.color.as_str().into());
             context.begin_path();
-            let circle_radius = settings.body_circle_radius * if settings.scale_body_circles_with_mass {
-                rendered_body.body.mass.cbrt()
-            } else {
-                1f64
-            };
+            let circle_radius = settings.body_circle_radius(&rendered_body.body);
             context.arc(rendered_body.body.position.x, -rendered_body.body.position.y, circle_radius, 0f64, 2f64 * PI).unwrap();
             context.fill();
             context.close_path();
         }
+
+        if let Some(rendered_body) = props.rendered_bodies.iter()
+            .find(|rendered_body| Some(rendered_body.label.id) == props.selected_body) {
+            let circle_radius = settings.body_circle_radius(&rendered_body.body);
+            context.set_stroke_style(&SELECTION_COLOR.into());
+            context.set_line_width(1.5f64);
+            context.begin_path();
+            context.arc(rendered_body.body.position.x, -rendered_body.body.position.y, circle_radius + SELECTION_RING_GAP, 0f64, 2f64 * PI).unwrap();
+            context.stroke();
+        }
+
+        if settings.labels_shown {
+            context.set_fill_style(&LABEL_COLOR.into());
+            context.set_font(LABEL_FONT);
+            for rendered_body in props.rendered_bodies.iter() {
+                let circle_radius = settings.body_circle_radius(&rendered_body.body);
+                context.fill_text(
+                    &rendered_body.label.name,
+                    rendered_body.body.position.x + circle_radius + LABEL_OFFSET,
+                    -rendered_body.body.position.y - circle_radius,
+                ).unwrap();
+            }
+        }
+
+        if vector_overlays.velocity_shown {
+            for body in bodies.iter() {
+                draw_arrow(&context, body.position, body.velocity * vector_overlays.velocity_scale, VELOCITY_ARROW_COLOR);
+            }
+        }
+
+        if vector_overlays.acceleration_shown {
+            for (body, acceleration) in bodies.iter().zip(accelerations(&bodies)) {
+                draw_arrow(&context, body.position, acceleration * vector_overlays.acceleration_scale, ACCELERATION_ARROW_COLOR);
+            }
+        }
     }
 
+    let body_at = {
+        let canvas_ref = canvas_ref.clone();
+        let rendered_bodies = props.rendered_bodies.clone();
+        let settings = settings.clone();
+        move |e: &MouseEvent| {
+            let canvas = canvas_ref.cast::<HtmlCanvasElement>()?;
+            let pointer = Vector2::new(e.offset_x() as f64, e.offset_y() as f64);
+            // the inverse of the translation of the context and of the flipped y axis
+            let position = Vector2::new(pointer.x - (canvas.width() / 2) as f64, (canvas.height() / 2) as f64 - pointer.y);
+
+            let id = rendered_bodies.iter().rev()
+                .filter(|rendered_body| (rendered_body.body.position - position).norm()
+                    <= settings.body_circle_radius(&rendered_body.body).max(HIT_RADIUS_MIN))
+                .min_by(|rendered_body1, rendered_body2| (rendered_body1.body.position - position).norm()
+                    .total_cmp(&(rendered_body2.body.position - position).norm()))
+                .map(|rendered_body| rendered_body.label.id)?;
+            Some((id, pointer))
+        }
+    };
+
+    let onmousemove = {
+        let hovered = hovered.clone();
+        let body_at = body_at.clone();
+        Callback::from(move |e: MouseEvent| hovered.set(body_at(&e)))
+    };
+    let onmouseleave = {
+        let hovered = hovered.clone();
+        Callback::from(move |_| hovered.set(None))
+    };
+    let onclick = props.select_callback.reform(move |e: MouseEvent| body_at(&e).map(|(id, _)| id));
+
+    let tooltip = (*hovered).and_then(|(id, pointer)| {
+        let rendered_body = props.rendered_bodies.iter().find(|rendered_body| rendered_body.label.id == id)?;
+        let body = &rendered_body.body;
+        let unit_system = settings.unit_system;
+        Some(html! {
+            <div class="absolute z-10 px-3 py-2 rounded bg-neutral-900/90 border border-neutral-600 font-mono text-xs whitespace-nowrap pointer-events-none"
+                style={format!("left: {}px; top: {}px;", pointer.x + TOOLTIP_OFFSET, pointer.y + TOOLTIP_OFFSET)}>
+                <div class="font-sans text-sm">{&rendered_body.label.name}</div>
+                if let Some(notes) = &rendered_body.label.notes {
+                    <div class="font-sans text-neutral-400">{notes}</div>
+                }
+                <div>{format!("mass: {}", unit_system.format(body.mass, Quantity::Mass))}</div>
+                <div>
+                    {format!("position: {}, {}",
+                        unit_system.format(body.position.x, Quantity::Length), unit_system.format(body.position.y, Quantity::Length))}
+                </div>
+                <div>
+                    {format!("velocity: {}, {}",
+                        unit_system.format(body.velocity.x, Quantity::Velocity), unit_system.format(body.velocity.y, Quantity::Velocity))}
+                </div>
+                <div>{format!("potential energy: {}", unit_system.format(rendered_body.potential_energy, Quantity::Energy))}</div>
+                <div>{format!("kinetic energy: {}", unit_system.format(body.kinetic_energy(), Quantity::Energy))}</div>
+            </div>
+        })
+    });
+
     html! {
-        <canvas ref={canvas_ref} class="absolute"/>
+        <>
+            <canvas ref={canvas_ref} class={if hovered.is_some() { "absolute cursor-pointer" } else { "absolute" }}
+                {onmousemove} {onmouseleave} {onclick}/>
+            {tooltip}
+        </>
+    }
+}
+
+fn draw_arrow(context: &CanvasRenderingContext2d, start: Vector2<f64>, vector: Vector2<f64>, color: &str) {
+    let length = vector.norm();
+    if length < f64::EPSILON || !length.is_finite() {
+        return;
+    }
+
+    let end = start + vector;
+    let direction = vector / length;
+    let head_length = ARROW_HEAD_LENGTH.min(length);
+    let head_base = end - direction * head_length;
+    let head_side = Vector2::new(-direction.y, direction.x) * head_length / 2f64;
+
+    context.set_stroke_style(&color.into());
+    context.set_fill_style(&color.into());
+    context.set_line_width(1.5f64);
+
+    context.begin_path();
+    context.move_to(start.x, -start.y);
+    context.line_to(head_base.x, -head_base.y);
+    context.stroke();
+
+    context.begin_path();
+    context.move_to(end.x, -end.y);
+    context.line_to((head_base + head_side).x, -(head_base + head_side).y);
+    context.line_to((head_base - head_side).x, -(head_base - head_side).y);
+    context.close_path();
+    context.fill();
+}
+
+/// Connects each pair of bodies with a line, the more opaque the stronger the force between them is.
+fn draw_force_lines(context: &CanvasRenderingContext2d, bodies: &[Body]) {
+    let forces = bodies.iter().enumerate()
+        .flat_map(|(index, body1)| bodies[index + 1..].iter()
+            .map(move |body2| (body1, body2, body1.force_towards(body2).norm())))
+        .collect::<Vec<_>>();
+    let force_max = forces.iter().map(|(_, _, force)| *force).fold(0f64, f64::max);
+    if force_max <= 0f64 || !force_max.is_finite() {
+        return;
+    }
+
+    context.set_line_width(1f64);
+    for (body1, body2, force) in forces {
+        context.set_stroke_style(&format!("rgba(255, 255, 255, {})", force / force_max).into());
+        context.begin_path();
+        context.move_to(body1.position.x, -body1.position.y);
+        context.line_to(body2.position.x, -body2.position.y);
+        context.stroke();
     }
 }
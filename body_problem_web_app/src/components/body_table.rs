@@ -1,21 +1,43 @@
-use yew::{Callback, function_component, Html, html, Properties};
+use yew::{Callback, function_component, Html, html, Properties, use_context, use_memo};
+
+use body_problem::generator::Generator;
+use body_problem::labeled::BodyId;
 
 use crate::components::body_table_row::BodyTableRow;
 use crate::components::button::Button;
+use crate::components::generate_dialog::GenerateDialog;
+use crate::models::expression_scope::SharedBodies;
 use crate::models::rendered_body::RenderedBody;
+use crate::models::settings::Settings;
+use crate::models::unit_system::Quantity;
 
 #[derive(Properties, PartialEq)]
 pub struct BodyTableProps {
     pub(crate) rendered_bodies: Vec<RenderedBody>,
     pub(crate) edit_allowed: bool,
     pub(crate) add_callback: Callback<()>,
+    /// Receives the generator and whether the generated bodies replace the current ones.
+    pub(crate) generate_callback: Callback<(Generator, bool)>,
     pub(crate) edit_callback: Callback<RenderedBody>,
-    pub(crate) remove_callback: Callback<usize>,
+    pub(crate) remove_callback: Callback<BodyId>,
+    pub(crate) undo_callback: Callback<()>,
+    pub(crate) redo_callback: Callback<()>,
+    pub(crate) undo_allowed: bool,
+    pub(crate) redo_allowed: bool,
+    pub(crate) selected_body: Option<BodyId>,
 }
 
 #[function_component(BodyTable)]
 pub fn body_table(props: &BodyTableProps) -> Html {
+    let unit_system = use_context::<Settings>().unwrap().unit_system;
+    // shared by the rows until the bodies change
+    let bodies = use_memo(
+        props.rendered_bodies.iter().map(|rendered_body| rendered_body.body.clone()).collect::<Vec<_>>(),
+        |bodies| SharedBodies::from(bodies.clone()),
+    );
     let add_callback = props.add_callback.clone();
+    let undo_callback = props.undo_callback.clone();
+    let redo_callback = props.redo_callback.clone();
 
     html! {
         <div class="flex flex-col gap-2 items-start">
@@ -23,13 +45,14 @@ pub fn body_table(props: &BodyTableProps) -> Html {
                 <table class="table-auto divide-y divide-neutral-600 min-w-full">
                     <thead class="text-neutral-500 whitespace-nowrap">
                         <tr class="divide-x divide-neutral-600">
-                            <th class="py-2 px-4">{"#"}</th>
+                            <th class="py-2 px-4">{"name"}</th>
+                            <th class="py-2 px-4">{"notes"}</th>
                             <th class="py-2 px-4">{"color"}</th>
-                            <th class="py-2 px-4">{"mass [kg]"}</th>
-                            <th class="py-2 px-4" colspan=2>{"position [px]"}</th>
-                            <th class="py-2 px-4" colspan=2>{"velocity [px/s]"}</th>
-                            <th class="py-2 px-4">{"potential energy [J]"}</th>
-                            <th class="py-2 px-4">{"kinetic energy [J]"}</th>
+                            <th class="py-2 px-4">{format!("mass [{}]", unit_system.unit(Quantity::Mass).symbol)}</th>
+                            <th class="py-2 px-4" colspan=2>{format!("position [{}]", unit_system.unit(Quantity::Length).symbol)}</th>
+                            <th class="py-2 px-4" colspan=2>{format!("velocity [{}]", unit_system.unit(Quantity::Velocity).symbol)}</th>
+                            <th class="py-2 px-4">{"potential energy"}</th>
+                            <th class="py-2 px-4">{"kinetic energy"}</th>
                             if props.edit_allowed { <th class="py-2 px-4">{"remove"}</th> }
                         </tr>
                     </thead>
@@ -37,15 +60,25 @@ pub fn body_table(props: &BodyTableProps) -> Html {
                     <tbody class="divide-y divide-neutral-600">
                         {props.rendered_bodies.iter().map(|rendered_body| {
                             html! {
-                                <BodyTableRow rendered_body={rendered_body.clone()} edit_allowed={props.edit_allowed} edit_callback={props.edit_callback.clone()} remove_callback={props.remove_callback.clone()}/>
+                                <BodyTableRow key={rendered_body.label.id.0} rendered_body={rendered_body.clone()} bodies={(*bodies).clone()} edit_allowed={props.edit_allowed}
+                                    selected={props.selected_body == Some(rendered_body.label.id)} edit_callback={props.edit_callback.clone()} remove_callback={props.remove_callback.clone()}/>
                             }
                         }).collect::<Html>()}
                     </tbody>
                 </table>
             </div>
-            <Button onclick={Callback::from(move |_| add_callback.emit(()))} class="py-2 px-4">
-                 <i class="fa-solid fa-plus mr-2"></i>{"add"}
-            </Button>
+            <div class="flex flex-row gap-3">
+                <Button onclick={Callback::from(move |_| add_callback.emit(()))} class="py-2 px-4">
+                     <i class="fa-solid fa-plus mr-2"></i>{"add"}
+                </Button>
+                <GenerateDialog generate_callback={props.generate_callback.clone()}/>
+                <Button onclick={Callback::from(move |_| undo_callback.emit(()))} disabled={!props.undo_allowed} class="py-2 px-4">
+                     <i class="fa-solid fa-rotate-left mr-2"></i>{"undo"}
+                </Button>
+                <Button onclick={Callback::from(move |_| redo_callback.emit(()))} disabled={!props.redo_allowed} class="py-2 px-4">
+                     <i class="fa-solid fa-rotate-right mr-2"></i>{"redo"}
+                </Button>
+            </div>
         </div>
     }
 }
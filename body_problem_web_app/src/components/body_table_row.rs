@@ -1,76 +1,46 @@
 use web_sys::{FocusEvent, HtmlInputElement, InputEvent};
 use web_sys::wasm_bindgen::JsCast;
-use yew::{Callback, function_component, Html, html, Properties};
+use yew::{Callback, classes, function_component, Html, html, Properties, use_context};
+
+use body_problem::Body;
+use body_problem::labeled::BodyId;
 
 use crate::components::button::Button;
+use crate::components::validated_input::ValidatedInput;
+use crate::models::expression_scope::{ExpressionScope, SharedBodies};
+use crate::models::validator::Validator;
 use crate::models::rendered_body::RenderedBody;
+use crate::models::settings::Settings;
+use crate::models::unit_system::{Quantity, UnitSystem};
 
 #[derive(PartialEq, Properties)]
 pub struct BodyTableRowProps {
     pub(crate) rendered_body: RenderedBody,
+    /// All the bodies, which the expressions entered can refer to.
+    pub(crate) bodies: SharedBodies,
     pub(crate) edit_allowed: bool,
+    /// Whether the body is selected on the canvas.
+    pub(crate) selected: bool,
     pub(crate) edit_callback: Callback<RenderedBody>,
-    pub(crate) remove_callback: Callback<usize>,
+    pub(crate) remove_callback: Callback<BodyId>,
 }
 
 #[function_component]
 pub fn BodyTableRow(props: &BodyTableRowProps) -> Html {
     let rendered_body = &props.rendered_body;
+    let unit_system = use_context::<Settings>().unwrap().unit_system;
 
     html! {
-        <tr class="font-mono text-lg divide-x divide-neutral-600">
-            <td class="py-2 px-4 text-center">
-                {rendered_body.index + 1}
-            </td>
-            <td class="px-4 align-middle text-center">
-            {
-                if props.edit_allowed {
-                    let rendered_body = rendered_body.clone();
-                    html! {
-                        <input type="color" class="bg-neutral-800 w-12 h-6" value={rendered_body.color.to_string()}
-                        oninput={props.edit_callback.reform(move |e: InputEvent| {
-                            let mut rendered_body = rendered_body.clone();
-                            rendered_body.color = e.target().unwrap().unchecked_into::<HtmlInputElement>().value();
-                            rendered_body
-                        })}
-                        />
-                    }
-                } else {
-                    html! {
-                        <div style={format!("background-color: {};", rendered_body.color)} class="w-12 h-6 m-auto"></div>
-                    }
-                }
-            }
-            </td>
-            {
-                if props.edit_allowed {
-                    let rendered_body = rendered_body.clone();
-                    html! {
-                        <td>
-                            <input type="text" class="bg-neutral-800 py-1 px-3 text-right min-w-full" value={rendered_body.body.mass.to_string()}
-                            onblur={props.edit_callback.reform(move |e: FocusEvent| {
-                                let mut rendered_body = rendered_body.clone();
-                                rendered_body.body.mass = e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rendered_body.body.mass);
-                                rendered_body
-                            })}
-                            />
-                        </td>
-                    }
-                } else {
-                    html! {
-                        <td class="py-2 px-4 text-right">{rendered_body.body.mass}</td>
-                    }
-                }
-            }
+        <tr class={classes!("font-mono", "text-lg", "divide-x", "divide-neutral-600", props.selected.then_some("bg-neutral-700"))}>
             {
                 if props.edit_allowed {
                     let rendered_body = rendered_body.clone();
                     html! {
                         <td>
-                            <input type="text" class="bg-neutral-800 py-1 px-3 text-right min-w-full" value={rendered_body.body.position.x.to_string()}
+                            <input type="text" class="bg-neutral-800 py-1 px-3 min-w-full" value={rendered_body.label.name.clone()}
                             onblur={props.edit_callback.reform(move |e: FocusEvent| {
                                 let mut rendered_body = rendered_body.clone();
-                                rendered_body.body.position.x = e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rendered_body.body.position.x);
+                                rendered_body.label.name = e.target().unwrap().unchecked_into::<HtmlInputElement>().value();
                                 rendered_body
                             })}
                             />
@@ -78,7 +48,7 @@ pub fn BodyTableRow(props: &BodyTableRowProps) -> Html {
                     }
                 } else {
                     html! {
-                        <td class="py-2 px-4 text-right">{format!("{:\u{00a0}>10.1}", rendered_body.body.position.x)}</td>
+                        <td class="py-2 px-4 whitespace-nowrap" title={tooltip(rendered_body)}>{&rendered_body.label.name}</td>
                     }
                 }
             }
@@ -87,10 +57,12 @@ pub fn BodyTableRow(props: &BodyTableRowProps) -> Html {
                     let rendered_body = rendered_body.clone();
                     html! {
                         <td>
-                            <input type="text" class="bg-neutral-800 py-1 px-3 text-right min-w-full" value={rendered_body.body.position.y.to_string()}
+                            <input type="text" class="bg-neutral-800 py-1 px-3 min-w-full font-sans text-base"
+                            value={rendered_body.label.notes.clone().unwrap_or_default()}
                             onblur={props.edit_callback.reform(move |e: FocusEvent| {
                                 let mut rendered_body = rendered_body.clone();
-                                rendered_body.body.position.y = e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rendered_body.body.position.y);
+                                let notes = e.target().unwrap().unchecked_into::<HtmlInputElement>().value();
+                                rendered_body.label.notes = (!notes.trim().is_empty()).then_some(notes);
                                 rendered_body
                             })}
                             />
@@ -98,59 +70,46 @@ pub fn BodyTableRow(props: &BodyTableRowProps) -> Html {
                     }
                 } else {
                     html! {
-                        <td class="py-2 px-4 text-right">{format!("{:\u{00a0}>10.1}", rendered_body.body.position.y)}</td>
-                    }
-                }
-            }
-            {
-                if props.edit_allowed {
-                    let rendered_body = rendered_body.clone();
-                    html! {
-                        <td>
-                            <input type="text" class="bg-neutral-800 py-1 px-3 text-right min-w-full" value={rendered_body.body.velocity.x.to_string()}
-                            onblur={props.edit_callback.reform(move |e: FocusEvent| {
-                                let mut rendered_body = rendered_body.clone();
-                                rendered_body.body.velocity.x = e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rendered_body.body.velocity.x);
-                                rendered_body
-                            })}
-                            />
+                        <td class="py-2 px-4 max-w-48 truncate font-sans text-base" title={tooltip(rendered_body)}>
+                            {rendered_body.label.notes.clone().unwrap_or_default()}
                         </td>
                     }
-                } else {
-                    html! {
-                        <td class="py-2 px-4 text-right">{format!("{:\u{00a0}>10.1}", rendered_body.body.velocity.x)}</td>
-                    }
                 }
             }
+            <td class="px-4 align-middle text-center">
             {
                 if props.edit_allowed {
                     let rendered_body = rendered_body.clone();
                     html! {
-                        <td>
-                            <input type="text" class="bg-neutral-800 py-1 px-3 text-right min-w-full" value={rendered_body.body.velocity.y.to_string()}
-                            onblur={props.edit_callback.reform(move |e: FocusEvent| {
-                                let mut rendered_body = rendered_body.clone();
-                                rendered_body.body.velocity.y = e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rendered_body.body.velocity.y);
-                                rendered_body
-                            })}
-                            />
-                        </td>
+                        <input type="color" class="bg-neutral-800 w-12 h-6" value={rendered_body.color.to_string()}
+                        oninput={props.edit_callback.reform(move |e: InputEvent| {
+                            let mut rendered_body = rendered_body.clone();
+                            rendered_body.color = e.target().unwrap().unchecked_into::<HtmlInputElement>().value();
+                            rendered_body
+                        })}
+                        />
                     }
                 } else {
                     html! {
-                        <td class="py-2 px-4 text-right">{format!("{:\u{00a0}>10.1}", rendered_body.body.velocity.y)}</td>
+                        <div style={format!("background-color: {};", rendered_body.color)} class="w-12 h-6 m-auto"></div>
                     }
                 }
             }
-            <td class="py-2 px-4 text-right">{format!("{:\u{00a0}>30.1}", rendered_body.potential_energy)}</td>
-            <td class="py-2 px-4 text-right">{format!("{:\u{00a0}>30.1}", rendered_body.body.kinetic_energy())}</td>
+            </td>
+            {quantity_cell(props, unit_system, "mass", Quantity::Mass, |body| body.mass, |body, value| body.mass = value)}
+            {quantity_cell(props, unit_system, "x", Quantity::Length, |body| body.position.x, |body, value| body.position.x = value)}
+            {quantity_cell(props, unit_system, "y", Quantity::Length, |body| body.position.y, |body, value| body.position.y = value)}
+            {quantity_cell(props, unit_system, "vx", Quantity::Velocity, |body| body.velocity.x, |body, value| body.velocity.x = value)}
+            {quantity_cell(props, unit_system, "vy", Quantity::Velocity, |body| body.velocity.y, |body, value| body.velocity.y = value)}
+            <td class="py-2 px-4 text-right whitespace-nowrap">{unit_system.format(rendered_body.potential_energy, Quantity::Energy)}</td>
+            <td class="py-2 px-4 text-right whitespace-nowrap">{unit_system.format(rendered_body.body.kinetic_energy(), Quantity::Energy)}</td>
             {
                 props.edit_allowed.then(|| {
                     let rendered_body = rendered_body.clone();
 
                     html! {
                         <td class="p-1 text-center">
-                            <Button onclick={props.remove_callback.reform(move |_| rendered_body.index)} class="py-1 px-3">
+                            <Button onclick={props.remove_callback.reform(move |_| rendered_body.label.id)} class="py-1 px-3">
                                 <i class="fa-solid fa-xmark"></i>
                             </Button>
                         </td>
@@ -160,3 +119,45 @@ pub fn BodyTableRow(props: &BodyTableRowProps) -> Html {
         </tr>
     }
 }
+
+/// Returns the text shown when hovering over the body, its name followed by its notes.
+pub(crate) fn tooltip(rendered_body: &RenderedBody) -> String {
+    match &rendered_body.label.notes {
+        Some(notes) => format!("{}\n{notes}", rendered_body.label.name),
+        None => rendered_body.label.name.clone(),
+    }
+}
+
+/// Returns the cell of a quantity of the body, an input accepting an expression if the body can be edited. The field
+/// name tells the inputs of the same quantity apart, e.g. `vx` and `vy`.
+fn quantity_cell(
+    props: &BodyTableRowProps,
+    unit_system: UnitSystem,
+    field: &str,
+    quantity: Quantity,
+    value: fn(&Body) -> f64,
+    set_value: fn(&mut Body, f64),
+) -> Html {
+    let rendered_body = props.rendered_body.clone();
+
+    if props.edit_allowed {
+        let validator = Validator::new(ExpressionScope::new(unit_system, Some(quantity), props.bodies.clone(), Some(rendered_body.index)));
+        let validator = if quantity == Quantity::Mass { validator.mass() } else { validator };
+        html! {
+            <td>
+                <ValidatedInput id={format!("input_body_{}_{field}", rendered_body.label.id.0)} class="min-w-full text-base"
+                value={unit_system.format_input(value(&rendered_body.body), quantity)} validator={validator}
+                on_input={props.edit_callback.reform(move |value: f64| {
+                    let mut rendered_body = rendered_body.clone();
+                    set_value(&mut rendered_body.body, value);
+                    rendered_body
+                })}
+                />
+            </td>
+        }
+    } else {
+        html! {
+            <td class="py-2 px-4 text-right whitespace-nowrap">{unit_system.format(value(&rendered_body.body), quantity)}</td>
+        }
+    }
+}
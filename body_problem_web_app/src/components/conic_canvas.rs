@@ -0,0 +1,93 @@
+use body_problem::kepler::OrbitalElements;
+use body_problem::labeled::BodyId;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use web_sys::js_sys::Array;
+use web_sys::wasm_bindgen::JsValue;
+use yew::{function_component, html, use_effect_with, use_node_ref, use_state, Html, Properties};
+
+use crate::models::rendered_body::RenderedBody;
+use crate::utils::{CanvasClear, SimulationCanvasInitialize};
+
+const CONIC_LINE_WIDTH: f64 = 1f64;
+const CONIC_POINT_COUNT: usize = 360;
+const CONIC_LINE_DASH: [f64; 2] = [2f64, 4f64];
+
+#[derive(Properties, PartialEq)]
+pub struct ConicCanvasProps {
+    pub(crate) rendered_bodies: Vec<RenderedBody>,
+    /// The body the Kepler orbits are drawn around, if any.
+    pub(crate) primary: Option<BodyId>,
+}
+
+/// The osculating Kepler orbits, on a layer of their own as they change with every state unlike the trajectories.
+#[function_component(ConicCanvas)]
+pub fn conic_canvas(props: &ConicCanvasProps) -> Html {
+    let canvas_ref = use_node_ref();
+    let canvas = canvas_ref.cast::<HtmlCanvasElement>();
+    let context = use_state(|| None);
+
+    {
+        let context = context.clone();
+        // on each change of the canvas (e.g. window resize)
+        use_effect_with(
+            canvas.clone(),
+            move |canvas| {
+                let mut resize_listener = None;
+                if let Some(canvas) = canvas {
+                    let canvas: HtmlCanvasElement = canvas.clone();
+                    resize_listener = Some(canvas.initialize_for_simulation(context, true));
+                }
+
+                move || drop(resize_listener)
+            },
+        );
+    }
+
+    if let (Some(context), Some(_)) = ((*context).clone(), canvas) {
+        let context: CanvasRenderingContext2d = context;
+        context.clear().unwrap();
+
+        if let Some(primary) = props.primary {
+            draw_conics(&context, &props.rendered_bodies, primary);
+        }
+    }
+
+    html! {
+        <canvas ref={canvas_ref} class="absolute"/>
+    }
+}
+
+/// Draws the Kepler orbit of each of the bodies relative to the primary, dotted in the body's color.
+fn draw_conics(context: &CanvasRenderingContext2d, rendered_bodies: &[RenderedBody], primary: BodyId) {
+    let Some(primary) = rendered_bodies.iter().find(|rendered_body| rendered_body.label.id == primary) else {
+        return;
+    };
+    let canvas = context.canvas().unwrap();
+    // far enough to leave the canvas from anywhere on it
+    let distance_max = (canvas.width() + canvas.height()) as f64;
+
+    context.save();
+    context.set_line_dash(&CONIC_LINE_DASH.iter().map(|&length| JsValue::from_f64(length)).collect::<Array>()).unwrap();
+    context.set_line_width(CONIC_LINE_WIDTH);
+
+    for rendered_body in rendered_bodies.iter().filter(|rendered_body| rendered_body.label.id != primary.label.id) {
+        let Some(orbital_elements) = OrbitalElements::osculating(&rendered_body.body, &primary.body) else {
+            continue;
+        };
+        let points = orbital_elements.points(CONIC_POINT_COUNT, distance_max);
+        let Some(start) = points.first() else {
+            continue;
+        };
+
+        let start = primary.body.position + start;
+        context.set_stroke_style(&rendered_body.color.as_str().into());
+        context.begin_path();
+        context.move_to(start.x, -start.y);
+        for point in points.iter().skip(1).map(|point| primary.body.position + point) {
+            context.line_to(point.x, -point.y);
+        }
+        context.stroke();
+    }
+
+    context.restore();
+}
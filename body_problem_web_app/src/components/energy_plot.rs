@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use web_sys::wasm_bindgen::JsCast;
+use yew::{function_component, html, use_context, use_mut_ref, use_node_ref, Html, Properties};
+
+use crate::models::rendered_simulation_state::RenderedSimulationState;
+use crate::models::energy_sample::EnergySample;
+use crate::models::settings::Settings;
+use crate::models::unit_system::{Quantity, UnitSystem};
+
+const ENERGY_PLOT_DURATION: Duration = Duration::from_secs(20);
+const PLOT_HEIGHT: f64 = 150f64;
+const PLOT_PADDING: f64 = 20f64;
+
+struct Plot {
+    title: &'static str,
+    /// The quantity the values are of, formatted in the unit system, or None if they are ratios.
+    quantity: Option<Quantity>,
+    series: Vec<PlotSeries>,
+}
+
+struct PlotSeries {
+    label: &'static str,
+    color: &'static str,
+    values: Vec<f64>,
+}
+
+/// The samples of the last [`ENERGY_PLOT_DURATION`] and the total energy the relative errors are measured from.
+#[derive(Default)]
+struct EnergyHistory {
+    samples: VecDeque<EnergySample>,
+    total_energy_initial: f64,
+}
+
+impl EnergyHistory {
+    fn push(&mut self, sample: EnergySample) {
+        if self.samples.back() == Some(&sample) {
+            return;
+        }
+        // The simulation was reset or edited, so the history no longer applies.
+        if self.samples.back().is_some_and(|last| last.recorded_after >= sample.recorded_after) {
+            self.samples.clear();
+        }
+
+        if self.samples.is_empty() {
+            self.total_energy_initial = sample.total_energy();
+        }
+        let recorded_after = sample.recorded_after;
+        self.samples.push_back(sample);
+
+        while self.samples.front().is_some_and(|first| recorded_after - first.recorded_after > ENERGY_PLOT_DURATION) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct EnergyPlotProps {
+    pub(crate) rendered_state: RenderedSimulationState,
+}
+
+#[function_component(EnergyPlot)]
+pub fn energy_plot(props: &EnergyPlotProps) -> Html {
+    let canvas_ref = use_node_ref();
+    let unit_system = use_context::<Settings>().unwrap().unit_system;
+    // kept outside the state, as it follows the rendered state without causing renders itself
+    let history = use_mut_ref(EnergyHistory::default);
+
+    history.borrow_mut().push(EnergySample::new(
+        &props.rendered_state.rendered_bodies.iter()
+            .map(|rendered_body| rendered_body.body.clone())
+            .collect::<Vec<_>>(),
+        props.rendered_state.duration_elapsed_total,
+    ));
+    let history = history.borrow();
+    let (energy_samples, total_energy_initial) = (&history.samples, history.total_energy_initial);
+
+    if let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() {
+        let width = canvas.parent_element().map(|parent| parent.client_width()).unwrap_or(0).max(0) as u32;
+        let height = (3f64 * PLOT_HEIGHT) as u32;
+        if canvas.width() != width || canvas.height() != height {
+            canvas.set_width(width);
+            canvas.set_height(height);
+        }
+
+        let context = canvas.get_context("2d").unwrap().unwrap()
+            .dyn_into::<CanvasRenderingContext2d>().unwrap();
+        context.clear_rect(0f64, 0f64, width as f64, height as f64);
+
+        let times = energy_samples.iter()
+            .map(|energy_sample| energy_sample.recorded_after.as_secs_f64())
+            .collect::<Vec<_>>();
+        let time_max = props.rendered_state.duration_elapsed_total.max(ENERGY_PLOT_DURATION).as_secs_f64();
+        let time_range = (time_max - ENERGY_PLOT_DURATION.as_secs_f64(), time_max);
+
+        let plots = [
+            Plot {
+                title: "energy",
+                quantity: Some(Quantity::Energy),
+                series: vec![
+                    PlotSeries {
+                        label: "kinetic",
+                        color: "#5a8cc8",
+                        values: energy_samples.iter().map(|sample| sample.kinetic_energy).collect(),
+                    },
+                    PlotSeries {
+                        label: "potential",
+                        color: "#ffff3f",
+                        values: energy_samples.iter().map(|sample| sample.potential_energy).collect(),
+                    },
+                    PlotSeries {
+                        label: "total",
+                        color: "#ffffff",
+                        values: energy_samples.iter().map(EnergySample::total_energy).collect(),
+                    },
+                ],
+            },
+            Plot {
+                title: "relative energy error",
+                quantity: None,
+                series: vec![
+                    PlotSeries {
+                        label: "",
+                        color: "#e05050",
+                        values: energy_samples.iter()
+                            .map(|sample| {
+                                let error = sample.total_energy() - total_energy_initial;
+                                if total_energy_initial == 0f64 {
+                                    error
+                                } else {
+                                    error / total_energy_initial.abs()
+                                }
+                            })
+                            .collect(),
+                    },
+                ],
+            },
+            Plot {
+                title: "angular momentum",
+                quantity: Some(Quantity::AngularMomentum),
+                series: vec![
+                    PlotSeries {
+                        label: "",
+                        color: "#50c878",
+                        values: energy_samples.iter().map(|sample| sample.angular_momentum).collect(),
+                    },
+                ],
+            },
+        ];
+
+        for (plot_index, plot) in plots.iter().enumerate() {
+            draw_plot(&context, plot_index as f64 * PLOT_HEIGHT, width as f64, plot, &times, time_range, unit_system);
+        }
+    }
+
+    html! {
+        <div class="w-full">
+            <canvas ref={canvas_ref}/>
+        </div>
+    }
+}
+
+fn draw_plot(context: &CanvasRenderingContext2d, top: f64, width: f64, plot: &Plot, times: &[f64],
+             time_range: (f64, f64), unit_system: UnitSystem) {
+    let (title, series) = (plot.title, &plot.series);
+    let left = PLOT_PADDING;
+    let right = width - PLOT_PADDING;
+    let plot_top = top + PLOT_PADDING;
+    let plot_bottom = top + PLOT_HEIGHT - PLOT_PADDING;
+
+    let values = series.iter().flat_map(|series| series.values.iter()).filter(|value| value.is_finite());
+    let (mut value_min, mut value_max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+        (min.min(value), max.max(value))
+    });
+    if value_min > value_max {
+        (value_min, value_max) = (0f64, 0f64);
+    }
+    if value_min == value_max {
+        let margin = if value_min == 0f64 { 1f64 } else { value_min.abs() * 0.1 };
+        value_min -= margin;
+        value_max += margin;
+    }
+
+    let x = |time: f64| left + (time - time_range.0) / (time_range.1 - time_range.0) * (right - left);
+    let y = |value: f64| plot_bottom - (value - value_min) / (value_max - value_min) * (plot_bottom - plot_top);
+
+    context.set_stroke_style(&"#525252".into());
+    context.set_line_width(1f64);
+    context.stroke_rect(left, plot_top, right - left, plot_bottom - plot_top);
+
+    context.set_font("12px 'Ubuntu Mono', monospace");
+    context.set_fill_style(&"#a3a3a3".into());
+    context.fill_text(title, left, plot_top - 6f64).unwrap();
+    let format = |value: f64| match plot.quantity {
+        Some(quantity) => unit_system.format(value, quantity),
+        None => format!("{value:.3e}"),
+    };
+    context.fill_text(&format(value_max), left + 4f64, plot_top + 14f64).unwrap();
+    context.fill_text(&format(value_min), left + 4f64, plot_bottom - 4f64).unwrap();
+
+    let mut legend_x = left + context.measure_text(title).map(|metrics| metrics.width()).unwrap_or(0f64) + 16f64;
+    for series in series.iter().filter(|series| !series.label.is_empty()) {
+        context.set_fill_style(&series.color.into());
+        context.fill_text(series.label, legend_x, plot_top - 6f64).unwrap();
+        legend_x += context.measure_text(series.label).map(|metrics| metrics.width()).unwrap_or(0f64) + 12f64;
+    }
+
+    context.save();
+    context.begin_path();
+    context.rect(left, plot_top, right - left, plot_bottom - plot_top);
+    context.clip();
+    for series in series {
+        context.set_stroke_style(&series.color.into());
+        context.begin_path();
+        for (index, (&time, &value)) in times.iter().zip(series.values.iter()).enumerate() {
+            if index == 0 {
+                context.move_to(x(time), y(value));
+            } else {
+                context.line_to(x(time), y(value));
+            }
+        }
+        context.stroke();
+    }
+    context.restore();
+}
@@ -1,6 +1,8 @@
-use yew::{function_component, html, Html, Properties};
+use yew::{function_component, html, Html, Properties, use_context};
 
 use crate::models::rendered_body::RenderedBody;
+use crate::models::settings::Settings;
+use crate::models::unit_system::Quantity;
 
 #[derive(Properties, PartialEq)]
 pub struct EnergySumTableProps {
@@ -9,6 +11,7 @@ pub struct EnergySumTableProps {
 
 #[function_component(EnergySumTable)]
 pub fn energy_sum_table(props: &EnergySumTableProps) -> Html {
+    let unit_system = use_context::<Settings>().unwrap().unit_system;
     let potential_energy_sum = props.rendered_bodies.iter()
         .map(|rendered_body| rendered_body.potential_energy).sum::<f64>();
     let kinetic_energy_sum = props.rendered_bodies.iter()
@@ -20,7 +23,7 @@ pub fn energy_sum_table(props: &EnergySumTableProps) -> Html {
                 <thead class="text-neutral-500 whitespace-nowrap">
                     <tr class="divide-x divide-neutral-600">
                         <th class="py-2 px-4">{"energy type"}</th>
-                        <th class="py-2 px-4">{"sum [J]"}</th>
+                        <th class="py-2 px-4">{"sum"}</th>
                     </tr>
                 </thead>
 
@@ -28,19 +31,19 @@ pub fn energy_sum_table(props: &EnergySumTableProps) -> Html {
                     <tr class="divide-x divide-neutral-600">
                         <td class="py-2 px-4">{"potential"}</td>
                         <td class="py-2 px-4 font-mono text-lg">
-                            {format!("{:\u{00a0}>40.1}", potential_energy_sum)}
+                            {format!("{:\u{00a0}>24}", unit_system.format(potential_energy_sum, Quantity::Energy))}
                         </td>
                     </tr>
                     <tr class="divide-x divide-neutral-600">
                         <td class="py-2 px-4">{"kinetic"}</td>
                         <td class="py-2 px-4 font-mono text-lg">
-                            {format!("{:\u{00a0}>40.1}", kinetic_energy_sum)}
+                            {format!("{:\u{00a0}>24}", unit_system.format(kinetic_energy_sum, Quantity::Energy))}
                         </td>
                     </tr>
                     <tr class="divide-x divide-neutral-600">
                         <td class="py-2 px-4">{"total"}</td>
                         <td class="py-2 px-4 font-mono text-lg">
-                            {format!("{:\u{00a0}>40.1}", potential_energy_sum + kinetic_energy_sum)}
+                            {format!("{:\u{00a0}>24}", unit_system.format(potential_energy_sum + kinetic_energy_sum, Quantity::Energy))}
                         </td>
                     </tr>
                 </tbody>
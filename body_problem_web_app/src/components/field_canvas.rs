@@ -0,0 +1,274 @@
+use gloo_utils::document;
+use nalgebra::Vector2;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData, Path2d};
+use web_sys::wasm_bindgen::{Clamped, JsCast};
+use web_time::{Duration, Instant};
+use yew::{function_component, html, use_context, use_effect_with, use_mut_ref, use_node_ref, use_state, Html, Properties};
+
+use body_problem::Body;
+use body_problem::field::evaluate_field;
+
+use crate::models::field_layer::FieldQuantity;
+use crate::models::rendered_body::RenderedBody;
+use crate::models::settings::Settings;
+use crate::utils::SimulationCanvasInitialize;
+
+const FIELD_CELL_SIZE: f64 = 8f64;
+const FIELD_CONTOUR_COUNT: usize = 16;
+const FIELD_CONTOUR_COLOR: &str = "rgba(255, 255, 255, 0.3)";
+// the "magma" colour map
+const FIELD_COLOR_STOPS: [(f64, [f64; 3]); 5] = [
+    (0f64, [0f64, 0f64, 4f64]),
+    (0.25f64, [59f64, 15f64, 112f64]),
+    (0.5f64, [140f64, 41f64, 129f64]),
+    (0.75f64, [222f64, 73f64, 104f64]),
+    (1f64, [252f64, 253f64, 191f64]),
+];
+// dims the layer so that it does not draw attention from the bodies
+const FIELD_COLOR_BRIGHTNESS: f64 = 0.6f64;
+/// The least time between the samplings of the field while the simulation runs, as each takes long.
+const FIELD_REBUILD_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Properties, PartialEq)]
+pub struct FieldCanvasProps {
+    pub(crate) rendered_bodies: Vec<RenderedBody>,
+    /// Whether the field follows the bodies at once rather than at most every [`FIELD_REBUILD_INTERVAL`].
+    pub(crate) simulation_paused: bool,
+}
+
+#[function_component(FieldCanvas)]
+pub fn field_canvas(props: &FieldCanvasProps) -> Html {
+    let canvas_ref = use_node_ref();
+    let canvas = canvas_ref.cast::<HtmlCanvasElement>();
+    let context = use_state(|| None);
+    let settings = use_context::<Settings>().unwrap();
+    let cache = use_mut_ref(|| None::<FieldCache>);
+
+    {
+        let context = context.clone();
+        // on each change of the canvas (e.g. window resize)
+        use_effect_with(
+            canvas.clone(),
+            move |canvas| {
+                let mut resize_listener = None;
+                if let Some(canvas) = canvas {
+                    let canvas: HtmlCanvasElement = canvas.clone();
+                    resize_listener = Some(canvas.initialize_for_simulation(context, false));
+                }
+
+                move || drop(resize_listener)
+            },
+        );
+    }
+
+    if let (Some(context), Some(canvas)) = ((*context).clone(), canvas) {
+        let context: CanvasRenderingContext2d = context;
+        let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+
+        // The canvas is opaque, so it provides the black background of the simulation.
+        context.set_fill_style(&"#000000".into());
+        context.fill_rect(-width / 2f64, -height / 2f64, width, height);
+
+        let bodies = props.rendered_bodies.iter()
+            .map(|rendered_body| rendered_body.body.clone())
+            .collect::<Vec<Body>>();
+        let quantity = settings.field_layer.quantity;
+        let mut cache = cache.borrow_mut();
+        let rebuilt = match cache.as_ref() {
+            None => true,
+            Some(cache) if cache.quantity != quantity || cache.size != (width, height) => true,
+            Some(cache) => cache.bodies != bodies
+                && (props.simulation_paused || cache.built.elapsed() >= FIELD_REBUILD_INTERVAL),
+        };
+        if rebuilt {
+            let image = (quantity != FieldQuantity::None)
+                .then(|| FieldGrid::new(&bodies, quantity, width, height).image());
+            *cache = Some(FieldCache { bodies, quantity, size: (width, height), built: Instant::now(), image });
+        }
+
+        if let Some(image) = cache.as_ref().and_then(|cache| cache.image.as_ref()) {
+            image.draw(&context, settings.field_layer.contours_shown);
+        }
+    }
+
+    html! {
+        <canvas ref={canvas_ref} class="absolute"/>
+    }
+}
+
+/// The field layer drawn for the bodies, the quantity and the canvas size, reused until they change.
+struct FieldCache {
+    bodies: Vec<Body>,
+    quantity: FieldQuantity,
+    size: (f64, f64),
+    built: Instant,
+    /// None if no field is shown.
+    image: Option<FieldImage>,
+}
+
+/// The heatmap at the resolution of the cells and the contours, drawn onto the canvas as they are.
+struct FieldImage {
+    heatmap: HtmlCanvasElement,
+    contours: Path2d,
+    origin: Vector2<f64>,
+    size: Vector2<f64>,
+}
+
+impl FieldImage {
+    fn draw(&self, context: &CanvasRenderingContext2d, contours_shown: bool) {
+        // Each pixel of the heatmap is a cell.
+        context.set_image_smoothing_enabled(false);
+        context.draw_image_with_html_canvas_element_and_dw_and_dh(
+            &self.heatmap,
+            self.origin.x,
+            self.origin.y,
+            self.size.x,
+            self.size.y,
+        ).unwrap();
+
+        if contours_shown {
+            context.set_stroke_style(&FIELD_CONTOUR_COLOR.into());
+            context.set_line_width(1f64);
+            context.stroke_with_path(&self.contours);
+        }
+    }
+}
+
+/// The field sampled at the corners of square cells covering the canvas, on a logarithmic scale.
+struct FieldGrid {
+    columns: usize,
+    rows: usize,
+    origin: Vector2<f64>,
+    values: Vec<f64>,
+    value_min: f64,
+    value_max: f64,
+}
+
+impl FieldGrid {
+    fn new(bodies: &[Body], quantity: FieldQuantity, width: f64, height: f64) -> Self {
+        let columns = (width / FIELD_CELL_SIZE).ceil() as usize;
+        let rows = (height / FIELD_CELL_SIZE).ceil() as usize;
+        // the canvas coordinates of the top left corner
+        let origin = Vector2::new(-width / 2f64, -height / 2f64);
+
+        let points = (0..=rows)
+            .flat_map(|row| (0..=columns).map(move |column| {
+                let canvas_point = origin + Vector2::new(column as f64, row as f64) * FIELD_CELL_SIZE;
+                Vector2::new(canvas_point.x, -canvas_point.y)
+            }))
+            .collect::<Vec<_>>();
+
+        let values = evaluate_field(bodies, &points).into_iter()
+            .map(|sample| match quantity {
+                FieldQuantity::Potential => -sample.potential,
+                _ => sample.acceleration.norm(),
+            })
+            .map(|value| value.max(f64::MIN_POSITIVE).ln())
+            .collect::<Vec<_>>();
+
+        let value_min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let value_max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        Self { columns, rows, origin, values, value_min, value_max }
+    }
+
+    fn value(&self, column: usize, row: usize) -> f64 {
+        self.values[row * (self.columns + 1) + column]
+    }
+
+    fn normalized(&self, value: f64) -> f64 {
+        if self.value_max > self.value_min {
+            (value - self.value_min) / (self.value_max - self.value_min)
+        } else {
+            0f64
+        }
+    }
+
+    fn image(&self) -> FieldImage {
+        FieldImage {
+            heatmap: self.heatmap(),
+            contours: self.contours(),
+            origin: self.origin,
+            size: Vector2::new(self.columns as f64, self.rows as f64) * FIELD_CELL_SIZE,
+        }
+    }
+
+    /// Returns a canvas with a pixel per cell, coloured by the mean of the values at its corners.
+    fn heatmap(&self) -> HtmlCanvasElement {
+        let pixels = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .flat_map(|(column, row)| {
+                let value = (self.value(column, row) + self.value(column + 1, row)
+                    + self.value(column, row + 1) + self.value(column + 1, row + 1)) / 4f64;
+                let [red, green, blue] = color_map(self.normalized(value));
+                [red.round() as u8, green.round() as u8, blue.round() as u8, u8::MAX]
+            })
+            .collect::<Vec<u8>>();
+
+        let canvas = document().create_element("canvas").unwrap().unchecked_into::<HtmlCanvasElement>();
+        canvas.set_width(self.columns as u32);
+        canvas.set_height(self.rows as u32);
+        if !pixels.is_empty() {
+            let image_data = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), self.columns as u32, self.rows as u32)
+                .unwrap();
+            let context = canvas.get_context("2d").unwrap().unwrap().unchecked_into::<CanvasRenderingContext2d>();
+            context.put_image_data(&image_data, 0f64, 0f64).unwrap();
+        }
+        canvas
+    }
+
+    /// Returns the contours (equipotentials for the potential) found using the marching squares algorithm.
+    fn contours(&self) -> Path2d {
+        let path = Path2d::new().unwrap();
+        if self.value_max <= self.value_min {
+            return path;
+        }
+
+        let levels = (1..FIELD_CONTOUR_COUNT)
+            .map(|index| self.value_min + (self.value_max - self.value_min) * index as f64 / FIELD_CONTOUR_COUNT as f64)
+            .collect::<Vec<_>>();
+
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                // the corners in a clockwise order, starting at the top left
+                let corners = [(column, row), (column + 1, row), (column + 1, row + 1), (column, row + 1)]
+                    .map(|(column, row)| (
+                        self.origin + Vector2::new(column as f64, row as f64) * FIELD_CELL_SIZE,
+                        self.value(column, row),
+                    ));
+
+                for &level in levels.iter() {
+                    let crossings = (0..4)
+                        .filter_map(|index| {
+                            let (position1, value1) = corners[index];
+                            let (position2, value2) = corners[(index + 1) % 4];
+                            ((value1 < level) != (value2 < level)).then(|| {
+                                position1 + (position2 - position1) * ((level - value1) / (value2 - value1))
+                            })
+                        })
+                        .collect::<Vec<_>>();
+
+                    for segment in crossings.chunks_exact(2) {
+                        path.move_to(segment[0].x, segment[0].y);
+                        path.line_to(segment[1].x, segment[1].y);
+                    }
+                }
+            }
+        }
+
+        path
+    }
+}
+
+fn color_map(value: f64) -> [f64; 3] {
+    let value = value.clamp(0f64, 1f64);
+    let index = FIELD_COLOR_STOPS.iter()
+        .position(|(stop, _)| *stop >= value)
+        .unwrap_or(FIELD_COLOR_STOPS.len() - 1)
+        .max(1);
+    let (stop1, color1) = FIELD_COLOR_STOPS[index - 1];
+    let (stop2, color2) = FIELD_COLOR_STOPS[index];
+    let ratio = (value - stop1) / (stop2 - stop1);
+
+    [0, 1, 2].map(|channel| (color1[channel] + (color2[channel] - color1[channel]) * ratio) * FIELD_COLOR_BRIGHTNESS)
+}
@@ -0,0 +1,72 @@
+use nalgebra::Vector2;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use web_sys::js_sys::Array;
+use web_sys::wasm_bindgen::JsValue;
+use yew::{function_component, html, use_effect_with, use_node_ref, use_state, Html, Properties};
+
+use crate::models::rendered_body::RenderedBody;
+use crate::utils::{CanvasClear, SimulationCanvasInitialize};
+
+const FORECAST_LINE_DASH: [f64; 2] = [4f64, 6f64];
+
+#[derive(Properties, PartialEq)]
+pub struct ForecastCanvasProps {
+    pub(crate) rendered_bodies: Vec<RenderedBody>,
+    /// The predicted path of each of the bodies, if any.
+    pub(crate) paths: Option<Vec<Vec<Vector2<f64>>>>,
+}
+
+#[function_component(ForecastCanvas)]
+pub fn forecast_canvas(props: &ForecastCanvasProps) -> Html {
+    let canvas_ref = use_node_ref();
+    let canvas = canvas_ref.cast::<HtmlCanvasElement>();
+    let context = use_state(|| None);
+
+    {
+        let context = context.clone();
+        // on each change of the canvas (e.g. window resize)
+        use_effect_with(
+            canvas.clone(),
+            move |canvas| {
+                let mut resize_listener = None;
+                if let Some(canvas) = canvas {
+                    let canvas: HtmlCanvasElement = canvas.clone();
+                    resize_listener = Some(canvas.initialize_for_simulation(context, true));
+                }
+
+                move || drop(resize_listener)
+            },
+        );
+    }
+
+    if let (Some(context), Some(_)) = ((*context).clone(), canvas) {
+        let context: CanvasRenderingContext2d = context;
+        context.clear().unwrap();
+
+        if let Some(paths) = props.paths.as_ref() {
+            context.save();
+            context.set_line_dash(&FORECAST_LINE_DASH.iter().map(|&length| JsValue::from_f64(length)).collect::<Array>()).unwrap();
+            context.set_global_alpha(0.7f64);
+
+            for (rendered_body, path) in props.rendered_bodies.iter().zip(paths.iter()).rev() {
+                let Some(start) = path.first() else {
+                    continue;
+                };
+
+                context.set_stroke_style(&rendered_body.color.as_str().into());
+                context.begin_path();
+                context.move_to(start.x, -start.y);
+                for position in path.iter().skip(1) {
+                    context.line_to(position.x, -position.y);
+                }
+                context.stroke();
+            }
+
+            context.restore();
+        }
+    }
+
+    html! {
+        <canvas ref={canvas_ref} class="absolute"/>
+    }
+}
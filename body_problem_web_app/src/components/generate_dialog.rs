@@ -0,0 +1,130 @@
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use web_sys::wasm_bindgen::JsCast;
+use yew::{Callback, function_component, Html, html, Properties, use_context, use_state};
+
+use body_problem::generator::{Distribution, Generator};
+
+use crate::components::button::Button;
+use crate::components::validated_input::ValidatedInput;
+use crate::models::expression_scope::{ExpressionScope, SharedBodies};
+use crate::models::settings::Settings;
+use crate::models::unit_system::Quantity;
+use crate::models::validator::Validator;
+
+// about the scale of the initial bodies
+const GENERATOR_DEFAULT: Generator = Generator::new(Distribution::PlummerSphere, 100, 1e17, 200f64, 1);
+// the simulation slows down with the square of the number of bodies
+const GENERATED_COUNT_MAX: f64 = 1000f64;
+
+#[derive(PartialEq, Properties)]
+pub struct GenerateDialogProps {
+    /// Receives the generator and whether the generated bodies replace the current ones.
+    pub(crate) generate_callback: Callback<(Generator, bool)>,
+}
+
+#[function_component(GenerateDialog)]
+pub fn generate_dialog(props: &GenerateDialogProps) -> Html {
+    let unit_system = use_context::<Settings>().unwrap().unit_system;
+    let open = use_state(|| false);
+    let generator = use_state(|| GENERATOR_DEFAULT);
+    let replacing = use_state(|| false);
+
+    let set_generator = {
+        let generator = generator.clone();
+        move |update: fn(&mut Generator, f64)| {
+            let generator = generator.clone();
+            Callback::from(move |value: f64| {
+                let mut generator_new = *generator;
+                update(&mut generator_new, value);
+                generator.set(generator_new);
+            })
+        }
+    };
+
+    let generate_callback = {
+        let open = open.clone();
+        let generator = generator.clone();
+        let replacing = replacing.clone();
+        let generate_callback = props.generate_callback.clone();
+        Callback::from(move |_| {
+            generate_callback.emit((*generator, *replacing));
+            open.set(false);
+        })
+    };
+
+    html! {
+        <>
+            <Button onclick={{
+                let open = open.clone();
+                Callback::from(move |_| open.set(true))
+            }} class="py-2 px-4">
+                <i class="fa-solid fa-wand-magic-sparkles mr-2"></i>{"generate"}
+            </Button>
+            if *open {
+                <div class="fixed inset-0 z-10 flex items-center justify-center bg-black/60">
+                    <div class="bg-neutral-900 border border-neutral-600 rounded p-6 flex flex-col gap-4">
+                        <h2 class="text-lg font-semibold">{"generate bodies"}</h2>
+                        <div class="grid grid-cols-2 gap-3 items-center">
+                            <label for="input_generator_distribution">{"distribution"}</label>
+                            <select id="input_generator_distribution" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded"
+                                onchange={{
+                                    let generator = generator.clone();
+                                    Callback::from(move |e: Event| {
+                                        let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                                        let distribution = usize::try_from(index).ok()
+                                            .and_then(|index| Distribution::ALL.get(index).copied())
+                                            .unwrap_or(generator.distribution);
+                                        generator.set(Generator { distribution, ..*generator });
+                                    })
+                                }}>
+                                {for Distribution::ALL.iter().map(|distribution| html! {
+                                    <option selected={generator.distribution == *distribution}>{distribution.name()}</option>
+                                })}
+                            </select>
+                            <label for="input_generator_count">{"number of bodies"}</label>
+                            <ValidatedInput id="input_generator_count" class="w-40"
+                                value={generator.count.to_string()}
+                                validator={Validator::default().at_least(1f64).at_most(GENERATED_COUNT_MAX).integer()}
+                                on_input={set_generator(|generator, value| generator.count = value as usize)}/>
+                            <label for="input_generator_mass">{format!("total mass [{}]", unit_system.unit(Quantity::Mass).symbol)}</label>
+                            <ValidatedInput id="input_generator_mass" class="w-40"
+                                value={unit_system.format_input(generator.mass_total, Quantity::Mass)}
+                                validator={Validator::new(ExpressionScope::new(unit_system, Some(Quantity::Mass), SharedBodies::default(), None)).greater_than(0f64)}
+                                on_input={set_generator(|generator, value| generator.mass_total = value)}/>
+                            <label for="input_generator_radius">{format!("scale radius [{}]", unit_system.unit(Quantity::Length).symbol)}</label>
+                            <ValidatedInput id="input_generator_radius" class="w-40"
+                                value={unit_system.format_input(generator.radius_scale, Quantity::Length)}
+                                validator={Validator::new(ExpressionScope::new(unit_system, Some(Quantity::Length), SharedBodies::default(), None)).greater_than(0f64)}
+                                on_input={set_generator(|generator, value| generator.radius_scale = value)}/>
+                            <label for="input_generator_seed">{"seed"}</label>
+                            <ValidatedInput id="input_generator_seed" class="w-40"
+                                value={generator.seed.to_string()}
+                                validator={Validator::default().at_least(0f64).integer()}
+                                on_input={set_generator(|generator, value| generator.seed = value as u64)}/>
+                            <label for="input_generator_replacing">{"replace the bodies"}</label>
+                            <input id="input_generator_replacing" type="checkbox" class="accent-white justify-self-start"
+                                checked={*replacing}
+                                onchange={{
+                                    let replacing = replacing.clone();
+                                    Callback::from(move |e: Event| {
+                                        replacing.set(e.target().unwrap().unchecked_into::<HtmlInputElement>().checked());
+                                    })
+                                }}/>
+                        </div>
+                        <div class="flex flex-row gap-3 justify-end">
+                            <Button onclick={{
+                                let open = open.clone();
+                                Callback::from(move |_| open.set(false))
+                            }} class="py-2 px-4">
+                                {"cancel"}
+                            </Button>
+                            <Button onclick={generate_callback} class="py-2 px-4">
+                                <i class="fa-solid fa-wand-magic-sparkles mr-2"></i>{"generate"}
+                            </Button>
+                        </div>
+                    </div>
+                </div>
+            }
+        </>
+    }
+}
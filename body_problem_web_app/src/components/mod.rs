@@ -1,5 +1,5 @@
 pub mod app;
-mod simulation_panel;
+pub(crate) mod simulation_panel;
 mod body_canvas;
 mod body_table;
 mod body_table_row;
@@ -8,4 +8,14 @@ mod button;
 mod trajectory_canvas;
 mod validated_input;
 mod footer;
+mod generate_dialog;
 mod energy_sum_table;
+
+mod session_slots;
+mod preset_picker;
+mod energy_plot;
+mod field_canvas;
+mod forecast_canvas;
+mod conic_canvas;
+mod webgl_canvas;
+mod performance_hud;
\ No newline at end of file
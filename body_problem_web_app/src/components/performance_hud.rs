@@ -0,0 +1,53 @@
+use web_time::Duration;
+use yew::{function_component, html, Html, Properties};
+
+use crate::agents::simulation_reactor::FrameStatistics;
+
+// The requested speed is considered sustained down to this share of it, as the frames vary somewhat.
+const SPEED_SUSTAINED_RATIO_MIN: f64 = 0.95;
+
+#[derive(Properties, PartialEq)]
+pub struct PerformanceHudProps {
+    /// The statistics of the latest frames, the newest last.
+    pub(crate) statistics: Vec<FrameStatistics>,
+}
+
+/// An overlay with the averages of the frame statistics reported by the agent.
+#[function_component(PerformanceHud)]
+pub fn performance_hud(props: &PerformanceHudProps) -> Html {
+    let Some(statistics_last) = props.statistics.last() else {
+        return html! {
+            <div class="absolute top-2 left-2 px-3 py-2 rounded bg-neutral-900/70 font-mono text-xs pointer-events-none">
+                {"no frames computed"}
+            </div>
+        };
+    };
+
+    let frames = props.statistics.len() as f64;
+    let steps = props.statistics.iter().map(|statistics| statistics.steps).sum::<u64>();
+    let frame_duration = props.statistics.iter().map(|statistics| statistics.frame_duration).sum::<Duration>();
+    let compute_duration = props.statistics.iter().map(|statistics| statistics.compute_duration).sum::<Duration>();
+    let compute_duration_max = props.statistics.iter().map(|statistics| statistics.compute_duration).max().unwrap();
+    let duration_simulated = props.statistics.iter().map(|statistics| statistics.duration_simulated).sum::<Duration>();
+
+    let speed_achieved = duration_simulated.as_secs_f64() / frame_duration.as_secs_f64();
+    let speed_requested = statistics_last.speed_requested;
+    let speed_sustained = speed_achieved >= speed_requested * SPEED_SUSTAINED_RATIO_MIN;
+
+    html! {
+        <div class="absolute top-2 left-2 px-3 py-2 rounded bg-neutral-900/70 font-mono text-xs pointer-events-none">
+            <div>{format!("steps per second: {:.0}", steps as f64 / frame_duration.as_secs_f64())}</div>
+            <div>{format!("frame rate: {:.1} fps", frames / frame_duration.as_secs_f64())}</div>
+            <div>
+                {format!("compute time: {:.2} ms (max {:.2} ms)",
+                    compute_duration.as_secs_f64() * 1000f64 / frames, compute_duration_max.as_secs_f64() * 1000f64)}
+            </div>
+            <div class={(!speed_sustained).then_some("text-red-400")}>
+                {format!("speed: {speed_achieved:.2}× of {speed_requested:.2}×")}
+            </div>
+            if !speed_sustained {
+                <div class="text-red-400">{"the requested speed cannot be sustained"}</div>
+            }
+        </div>
+    }
+}
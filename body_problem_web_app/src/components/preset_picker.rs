@@ -0,0 +1,42 @@
+use web_sys::{Event, HtmlSelectElement};
+use web_sys::wasm_bindgen::JsCast;
+use yew::{Callback, function_component, Html, html, Properties, use_state};
+
+use crate::components::button::Button;
+use crate::models::preset::Preset;
+
+#[derive(PartialEq, Properties)]
+pub struct PresetPickerProps {
+    pub(crate) load_callback: Callback<Preset>,
+}
+
+#[function_component(PresetPicker)]
+pub fn preset_picker(props: &PresetPickerProps) -> Html {
+    let preset = use_state(Preset::default);
+
+    html! {
+        <div class="flex flex-row gap-3 items-center">
+            <label for="input_preset" class="whitespace-nowrap">{"preset"}</label>
+            <select id="input_preset" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded"
+                onchange={{
+                    let preset = preset.clone();
+                    Callback::from(move |e: Event| {
+                        let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                        if let Some(preset_new) = usize::try_from(index).ok().and_then(|index| Preset::ALL.get(index)) {
+                            preset.set(*preset_new);
+                        }
+                    })
+                }}>
+                {for Preset::ALL.iter().map(|preset_option| html! {
+                    <option selected={*preset == *preset_option}>{preset_option.name()}</option>
+                })}
+            </select>
+            <Button onclick={props.load_callback.reform({
+                let preset = *preset;
+                move |_| preset
+            })} class="py-1 px-3">
+                <i class="fa-solid fa-folder-open mr-2"></i>{"load"}
+            </Button>
+        </div>
+    }
+}
@@ -0,0 +1,91 @@
+use web_sys::{HtmlInputElement, InputEvent};
+use web_sys::wasm_bindgen::JsCast;
+use yew::{Callback, function_component, Html, html, Properties, use_state};
+
+use crate::components::button::Button;
+use crate::models::session::Session;
+
+#[derive(PartialEq, Properties)]
+pub struct SessionSlotsProps {
+    pub(crate) session: Session,
+    pub(crate) load_callback: Callback<Session>,
+}
+
+#[function_component(SessionSlots)]
+pub fn session_slots(props: &SessionSlotsProps) -> Html {
+    let sessions_saved = use_state(Session::load_saved);
+    let name = use_state(String::new);
+
+    let save_callback = {
+        let sessions_saved = sessions_saved.clone();
+        let name = name.clone();
+        let session = props.session.clone();
+
+        Callback::from(move |_| {
+            let name_trimmed = name.trim();
+            if name_trimmed.is_empty() {
+                return;
+            }
+
+            let mut sessions_saved_new = (*sessions_saved).clone();
+            sessions_saved_new.insert(name_trimmed.to_string(), session.clone());
+            Session::store_saved(&sessions_saved_new);
+
+            sessions_saved.set(sessions_saved_new);
+            name.set(String::new());
+        })
+    };
+
+    html! {
+        <div class="flex flex-col gap-2 items-start">
+            <div class="flex flex-row gap-3">
+                <input type="text" placeholder="name"
+                    class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded"
+                    value={(*name).clone()}
+                    oninput={{
+                        let name = name.clone();
+                        Callback::from(move |e: InputEvent| {
+                            name.set(e.target().unwrap().unchecked_into::<HtmlInputElement>().value());
+                        })
+                    }}/>
+                <Button onclick={save_callback} disabled={name.trim().is_empty()} class="py-1 px-3">
+                    <i class="fa-solid fa-floppy-disk mr-2"></i>{"save"}
+                </Button>
+            </div>
+            if !sessions_saved.is_empty() {
+                <ul class="flex flex-col gap-1">
+                    {sessions_saved.iter().map(|(session_name, session)| {
+                        let load_callback = {
+                            let session = session.clone();
+                            props.load_callback.reform(move |_| session.clone())
+                        };
+                        let delete_callback = {
+                            let sessions_saved = sessions_saved.clone();
+                            let session_name = session_name.clone();
+
+                            Callback::from(move |_| {
+                                let mut sessions_saved_new = (*sessions_saved).clone();
+                                sessions_saved_new.remove(&session_name);
+                                Session::store_saved(&sessions_saved_new);
+
+                                sessions_saved.set(sessions_saved_new);
+                            })
+                        };
+
+                        html! {
+                            <li class="flex flex-row gap-3 items-center">
+                                <Button onclick={load_callback} class="py-1 px-3">
+                                    <i class="fa-solid fa-folder-open mr-2"></i>{"load"}
+                                </Button>
+                                <Button onclick={delete_callback} class="py-1 px-3">
+                                    <i class="fa-solid fa-trash"></i>
+                                </Button>
+                                <span>{session_name}</span>
+                            </li>
+                        }
+                    }).collect::<Html>()}
+                </ul>
+            }
+        </div>
+    }
+}
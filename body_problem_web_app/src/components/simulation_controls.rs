@@ -1,12 +1,26 @@
 use std::time::Duration;
 
-use web_sys::{HtmlInputElement, InputEvent, MouseEvent};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, InputEvent, MouseEvent};
 use web_sys::wasm_bindgen::JsCast;
 use yew::{Callback, function_component, Html, html, Properties, use_context};
 
+use body_problem::integrator::Integrator;
+use body_problem::labeled::BodyLabel;
+use body_problem::recorder::RecordingFormat;
+
+use crate::agents::simulation_reactor::{RecordingChange, RecordingStatus, STEP_MIN};
 use crate::components::button::Button;
 use crate::components::validated_input::ValidatedInput;
+use crate::models::field_layer::{FieldLayer, FieldQuantity};
+use crate::models::integration::Integration;
+use crate::models::renderer::Renderer;
 use crate::models::settings::Settings;
+use crate::models::trail_style::{TrailColoring, TrailStyle, TrailWidth};
+use crate::models::unit_system::{Quantity, UnitSystem};
+use crate::models::validator::Validator;
+use crate::models::vector_overlays::VectorOverlays;
+
+const TARGET_FPS_MAX: f64 = 240f64;
 
 #[derive(PartialEq, Properties)]
 pub struct SimulationControlsProps {
@@ -14,12 +28,26 @@ pub struct SimulationControlsProps {
     pub(crate) toggle_pause_callback: Callback<MouseEvent>,
     pub(crate) reset_callback: Callback<MouseEvent>,
     pub(crate) set_settings_callback: Callback<Settings>,
+    pub(crate) seek_callback: Callback<Duration>,
+    /// Advances the paused simulation by the number of steps.
+    pub(crate) step_callback: Callback<u64>,
+    /// Starts recording the trajectory for the export if true, otherwise stops.
+    pub(crate) record_callback: Callback<bool>,
+    /// Downloads the recorded trajectory in the format.
+    pub(crate) export_callback: Callback<RecordingFormat>,
+    pub(crate) recording_status: RecordingStatus,
+    pub(crate) seek_allowed: bool,
     pub(crate) duration_elapsed_total: Duration,
+    pub(crate) duration_recorded: Duration,
+    /// The bodies that can be chosen as the primary of the Kepler orbits.
+    pub(crate) body_labels: Vec<BodyLabel>,
 }
 
 #[function_component(SimulationControls)]
 pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
     let settings = use_context::<Settings>().unwrap();
+    let frame_duration = Duration::from_secs_f64(1f64 / settings.integration.target_fps);
+    let frame_steps = settings.integration.steps_per_frame();
 
     html! {
         <>
@@ -39,14 +67,67 @@ pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
                     <Button onclick={props.reset_callback.clone()} class="py-2 px-4 sm:w-24">
                         <i class="fa-solid fa-rotate-right mr-2"></i>{"reset"}
                     </Button>
+                    if props.simulation_paused {
+                        <Button onclick={props.seek_callback.reform({
+                            let duration_elapsed_total = props.duration_elapsed_total;
+                            move |_| duration_elapsed_total.saturating_sub(frame_duration)
+                        })} disabled={!props.seek_allowed || props.duration_elapsed_total.is_zero()} class="py-2 px-4">
+                            <i class="fa-solid fa-backward-step"></i>
+                        </Button>
+                        <Button onclick={props.step_callback.reform(move |_| frame_steps)}
+                            disabled={!props.seek_allowed} class="py-2 px-4">
+                            <i class="fa-solid fa-forward-step"></i>
+                        </Button>
+                    }
+                    <Button onclick={props.record_callback.reform({
+                        let recording = props.recording_status.recording;
+                        move |_| !recording
+                    })} class="py-2 px-4 whitespace-nowrap">
+                        {if props.recording_status.recording {
+                            html! {
+                                <><i class="fa-solid fa-stop mr-2"></i>{"stop recording"}</>
+                            }
+                        } else {
+                            html! {
+                                <><i class="fa-solid fa-circle mr-2"></i>{"record"}</>
+                            }
+                        }}
+                    </Button>
+                    <Button onclick={props.export_callback.reform(|_| RecordingFormat::Csv)}
+                        disabled={!props.seek_allowed || !props.recording_status.recorded} class="py-2 px-4 whitespace-nowrap">
+                        <i class="fa-solid fa-download mr-2"></i>{"export CSV"}
+                    </Button>
+                    <Button onclick={props.export_callback.reform(|_| RecordingFormat::Binary)}
+                        disabled={!props.seek_allowed || !props.recording_status.recorded} class="py-2 px-4 whitespace-nowrap">
+                        <i class="fa-solid fa-download mr-2"></i>{"export binary"}
+                    </Button>
+                    {match props.recording_status.change {
+                        RecordingChange::Full => html! {
+                            <span class="text-red-400 text-sm self-center">{"the recording is full and stopped"}</span>
+                        },
+                        RecordingChange::Reset => html! {
+                            <span class="text-red-400 text-sm self-center">{"the recording was reset as the bodies changed"}</span>
+                        },
+                        RecordingChange::Started | RecordingChange::Stopped => html! {},
+                    }}
                 </div>
                 <span>
                     {"duration elapsed: "}
                     <span class="font-mono text-lg">
-                        {format!("{:10.2}", props.duration_elapsed_total.as_secs_f64())}
+                        {format!("{:>16}", settings.unit_system.format(props.duration_elapsed_total.as_secs_f64(), Quantity::Time))}
                     </span>
-                    {" s"}
                 </span>
+                <input id="input_timeline_range" type="range" class="accent-white grow"
+                    min=0 max={props.duration_recorded.max(props.duration_elapsed_total).as_secs_f64().to_string()} step=0.01
+                    value={props.duration_elapsed_total.as_secs_f64().to_string()}
+                    disabled={!props.seek_allowed}
+                    oninput={props.seek_callback.reform({
+                        let duration_elapsed_total = props.duration_elapsed_total;
+                        move |e: InputEvent| {
+                            e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse::<f64>().ok()
+                                .map(Duration::from_secs_f64).unwrap_or(duration_elapsed_total)
+                        }
+                    })}/>
             </div>
             <div class="grid justify-items-stretch grid-cols-1 sm:grid-cols-2 xl:flex xl:justify-between gap-6">
                 <div class="flex flex-col grow">
@@ -61,9 +142,10 @@ pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
                         <div>
                             <ValidatedInput id="input_trajectory_duration_text" class="w-24"
                                 value={settings.trajectory_duration.as_millis().to_string()}
-                                on_input={props.set_settings_callback.reform(move |value: String| {
+                                validator={Validator::default().at_least(0f64).integer()}
+                                on_input={props.set_settings_callback.reform(move |value: f64| {
                                     Settings {
-                                        trajectory_duration: Duration::from_millis(value.parse::<u64>().unwrap_or(settings.trajectory_duration.as_millis() as u64)),
+                                        trajectory_duration: Duration::from_millis(value as u64),
                                         ..settings
                                     }
                                 })}/>
@@ -73,7 +155,7 @@ pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
                 <div class="flex flex-col grow">
                     <label for="input_simulation_speed_range" class="whitespace-nowrap">{"simulation speed"}</label>
                     <div class="flex flex-row gap-3">
-                        <input id="input_simulation_speed_range" type="range" class="accent-white grow" min=0 max=20 step=0.1 value={settings.simulation_speed.to_string()} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                        <input id="input_simulation_speed_range" type="range" class="accent-white grow" min=0 max=20 step=0.01 value={settings.simulation_speed.to_string()} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
                                 Settings {
                                     simulation_speed: e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse::<f64>().unwrap_or(settings.simulation_speed),
                                     ..settings
@@ -81,9 +163,30 @@ pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
                             })}/>
                         <ValidatedInput id="input_simulation_speed_text" class="w-24"
                             value={settings.simulation_speed.to_string()}
-                            on_input={props.set_settings_callback.reform(move |value: String| {
+                            validator={Validator::default().at_least(0f64)}
+                            on_input={props.set_settings_callback.reform(move |value: f64| {
+                                Settings {
+                                    simulation_speed: value,
+                                    ..settings
+                                }
+                            })}/>
+                    </div>
+                </div>
+                <div class="flex flex-col grow">
+                    <label for="input_forecast_duration_range" class="whitespace-nowrap">{"forecast while paused [ms]"}</label>
+                    <div class="flex flex-row gap-3">
+                        <input id="input_forecast_duration_range" type="range" class="accent-white grow" min=0 max=30_000 step=500 value={settings.forecast_duration.as_millis().to_string()} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                forecast_duration: Duration::from_millis(e.target().unwrap().unchecked_into::<HtmlInputElement>().value().parse::<u64>().unwrap_or(settings.forecast_duration.as_millis() as u64)),
+                                ..settings
+                            }
+                        })}/>
+                        <ValidatedInput id="input_forecast_duration_text" class="w-24"
+                            value={settings.forecast_duration.as_millis().to_string()}
+                            validator={Validator::default().at_least(0f64).integer()}
+                            on_input={props.set_settings_callback.reform(move |value: f64| {
                                 Settings {
-                                    simulation_speed: value.parse::<f64>().ok().filter(|&value| value >= 0f64).unwrap_or(settings.simulation_speed),
+                                    forecast_duration: Duration::from_millis(value as u64),
                                     ..settings
                                 }
                             })}/>
@@ -100,9 +203,10 @@ pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
                             })}/>
                         <ValidatedInput id="input_body_circle_radius_text" class="w-24"
                             value={settings.body_circle_radius.to_string()}
-                            on_input={props.set_settings_callback.reform(move |value: String| {
+                            validator={Validator::default().at_least(0f64)}
+                            on_input={props.set_settings_callback.reform(move |value: f64| {
                                 Settings {
-                                    body_circle_radius: if value.is_empty() { 0f64 } else {value.parse::<f64>().ok().filter(|&value| value >= 0f64).unwrap_or(settings.body_circle_radius)},
+                                    body_circle_radius: value,
                                     ..settings
                                 }
                             })}/>
@@ -118,6 +222,291 @@ pub fn simulation_controls(props: &SimulationControlsProps) -> Html {
                         })}/>
                 </div>
             </div>
+            <div class="grid justify-items-stretch grid-cols-1 sm:grid-cols-2 xl:flex xl:justify-start gap-6">
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_unit_system" class="whitespace-nowrap">{"units"}</label>
+                    <select id="input_unit_system" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={props.set_settings_callback.reform(move |e: Event| {
+                            let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                            Settings {
+                                unit_system: usize::try_from(index).ok()
+                                    .and_then(|index| UnitSystem::ALL.get(index).copied())
+                                    .unwrap_or(settings.unit_system),
+                                ..settings
+                            }
+                        })}>
+                        {for UnitSystem::ALL.iter().map(|unit_system| html! {
+                            <option selected={settings.unit_system == *unit_system}>{unit_system.name()}</option>
+                        })}
+                    </select>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_integrator" class="whitespace-nowrap">{"integrator"}</label>
+                    <select id="input_integrator" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={props.set_settings_callback.reform(move |e: Event| {
+                            let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                            Settings {
+                                integration: Integration {
+                                    integrator: usize::try_from(index).ok()
+                                        .and_then(|index| Integrator::ALL.get(index).copied())
+                                        .unwrap_or(settings.integration.integrator),
+                                    ..settings.integration
+                                },
+                                ..settings
+                            }
+                        })}>
+                        {for Integrator::ALL.iter().map(|integrator| html! {
+                            <option selected={settings.integration.integrator == *integrator}>{integrator.name()}</option>
+                        })}
+                    </select>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_integration_step" class="whitespace-nowrap">{"step [s]"}</label>
+                    <ValidatedInput id="input_integration_step" class="w-24"
+                        value={settings.integration.step.to_string()}
+                        validator={Validator::default().at_least(STEP_MIN)}
+                        on_input={props.set_settings_callback.reform(move |value: f64| {
+                            Settings {
+                                integration: Integration {
+                                    step: value,
+                                    ..settings.integration
+                                },
+                                ..settings
+                            }
+                        })}/>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_target_fps" class="whitespace-nowrap">{"target frame rate [fps]"}</label>
+                    <ValidatedInput id="input_target_fps" class="w-24"
+                        value={settings.integration.target_fps.to_string()}
+                        validator={Validator::default().greater_than(0f64).at_most(TARGET_FPS_MAX)}
+                        on_input={props.set_settings_callback.reform(move |value: f64| {
+                            Settings {
+                                integration: Integration {
+                                    target_fps: value,
+                                    ..settings.integration
+                                },
+                                ..settings
+                            }
+                        })}/>
+                </div>
+            </div>
+            <div class="grid justify-items-stretch grid-cols-1 sm:grid-cols-2 xl:flex xl:justify-start gap-6">
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_velocity_vectors_shown" class="whitespace-nowrap">{"velocity arrows"}</label>
+                    <input id="input_velocity_vectors_shown" type="checkbox" class="accent-white" checked={settings.vector_overlays.velocity_shown} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                vector_overlays: VectorOverlays {
+                                    velocity_shown: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                    ..settings.vector_overlays
+                                },
+                                ..settings
+                            }
+                        })}/>
+                    <label for="input_velocity_vector_scale" class="whitespace-nowrap">{"scale [s]"}</label>
+                    <ValidatedInput id="input_velocity_vector_scale" class="w-24"
+                        value={settings.vector_overlays.velocity_scale.to_string()}
+                        validator={Validator::default().at_least(0f64)}
+                        on_input={props.set_settings_callback.reform(move |value: f64| {
+                            Settings {
+                                vector_overlays: VectorOverlays {
+                                    velocity_scale: value,
+                                    ..settings.vector_overlays
+                                },
+                                ..settings
+                            }
+                        })}/>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_acceleration_vectors_shown" class="whitespace-nowrap">{"acceleration arrows"}</label>
+                    <input id="input_acceleration_vectors_shown" type="checkbox" class="accent-white" checked={settings.vector_overlays.acceleration_shown} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                vector_overlays: VectorOverlays {
+                                    acceleration_shown: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                    ..settings.vector_overlays
+                                },
+                                ..settings
+                            }
+                        })}/>
+                    <label for="input_acceleration_vector_scale" class="whitespace-nowrap">{"scale [s²]"}</label>
+                    <ValidatedInput id="input_acceleration_vector_scale" class="w-24"
+                        value={settings.vector_overlays.acceleration_scale.to_string()}
+                        validator={Validator::default().at_least(0f64)}
+                        on_input={props.set_settings_callback.reform(move |value: f64| {
+                            Settings {
+                                vector_overlays: VectorOverlays {
+                                    acceleration_scale: value,
+                                    ..settings.vector_overlays
+                                },
+                                ..settings
+                            }
+                        })}/>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_renderer" class="whitespace-nowrap">{"renderer"}</label>
+                    <select id="input_renderer" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={props.set_settings_callback.reform(move |e: Event| {
+                            Settings {
+                                renderer: match e.target().unwrap().unchecked_into::<HtmlSelectElement>().value().as_str() {
+                                    "webgl2" => Renderer::WebGl2,
+                                    _ => Renderer::Canvas2d,
+                                },
+                                ..settings
+                            }
+                        })}>
+                        <option value="canvas2d" selected={settings.renderer == Renderer::Canvas2d}>{"2D canvas"}</option>
+                        <option value="webgl2" selected={settings.renderer == Renderer::WebGl2}>{"WebGL 2"}</option>
+                    </select>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_field_quantity" class="whitespace-nowrap">{"field layer"}</label>
+                    <select id="input_field_quantity" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={props.set_settings_callback.reform(move |e: Event| {
+                            Settings {
+                                field_layer: FieldLayer {
+                                    quantity: match e.target().unwrap().unchecked_into::<HtmlSelectElement>().value().as_str() {
+                                        "potential" => FieldQuantity::Potential,
+                                        "field_magnitude" => FieldQuantity::FieldMagnitude,
+                                        _ => FieldQuantity::None,
+                                    },
+                                    ..settings.field_layer
+                                },
+                                ..settings
+                            }
+                        })}>
+                        <option value="none" selected={settings.field_layer.quantity == FieldQuantity::None}>{"none"}</option>
+                        <option value="potential" selected={settings.field_layer.quantity == FieldQuantity::Potential}>{"potential"}</option>
+                        <option value="field_magnitude" selected={settings.field_layer.quantity == FieldQuantity::FieldMagnitude}>{"field magnitude"}</option>
+                    </select>
+                    <label for="input_field_contours_shown" class="whitespace-nowrap">{"contours"}</label>
+                    <input id="input_field_contours_shown" type="checkbox" class="accent-white" checked={settings.field_layer.contours_shown} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                field_layer: FieldLayer {
+                                    contours_shown: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                    ..settings.field_layer
+                                },
+                                ..settings
+                            }
+                        })}/>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_force_lines_shown" class="whitespace-nowrap">{"force lines"}</label>
+                    <input id="input_force_lines_shown" type="checkbox" class="accent-white" checked={settings.vector_overlays.force_lines_shown} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                vector_overlays: VectorOverlays {
+                                    force_lines_shown: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                    ..settings.vector_overlays
+                                },
+                                ..settings
+                            }
+                        })}/>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_labels_shown" class="whitespace-nowrap">{"labels"}</label>
+                    <input id="input_labels_shown" type="checkbox" class="accent-white" checked={settings.labels_shown} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                labels_shown: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                ..settings
+                            }
+                        })}/>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_conic_primary" class="whitespace-nowrap">{"Kepler orbits around"}</label>
+                    <select id="input_conic_primary" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={{
+                            let body_labels = props.body_labels.clone();
+                            props.set_settings_callback.reform(move |e: Event| {
+                                let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                                // the first option is none
+                                Settings {
+                                    conic_primary: usize::try_from(index - 1).ok()
+                                        .and_then(|index| body_labels.get(index))
+                                        .map(|body_label| body_label.id),
+                                    ..settings
+                                }
+                            })
+                        }}>
+                        <option selected={settings.conic_primary.is_none()}>{"none"}</option>
+                        {for props.body_labels.iter().map(|body_label| html! {
+                            <option selected={settings.conic_primary == Some(body_label.id)}>{&body_label.name}</option>
+                        })}
+                    </select>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_trail_coloring" class="whitespace-nowrap">{"trail colour"}</label>
+                    <select id="input_trail_coloring" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={props.set_settings_callback.reform(move |e: Event| {
+                            let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                            Settings {
+                                trail_style: TrailStyle {
+                                    coloring: usize::try_from(index).ok()
+                                        .and_then(|index| TrailColoring::ALL.get(index).copied())
+                                        .unwrap_or(settings.trail_style.coloring),
+                                    ..settings.trail_style
+                                },
+                                ..settings
+                            }
+                        })}>
+                        {for TrailColoring::ALL.iter().map(|coloring| html! {
+                            <option selected={settings.trail_style.coloring == *coloring}>{coloring.name()}</option>
+                        })}
+                    </select>
+                    if settings.trail_style.coloring == TrailColoring::Distance {
+                        <label for="input_trail_reference" class="whitespace-nowrap">{"to"}</label>
+                        <select id="input_trail_reference" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={{
+                                let body_labels = props.body_labels.clone();
+                                props.set_settings_callback.reform(move |e: Event| {
+                                    let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                                    // the first option is none
+                                    Settings {
+                                        trail_style: TrailStyle {
+                                            reference: usize::try_from(index - 1).ok()
+                                                .and_then(|index| body_labels.get(index))
+                                                .map(|body_label| body_label.id),
+                                            ..settings.trail_style
+                                        },
+                                        ..settings
+                                    }
+                                })
+                            }}>
+                            <option selected={settings.trail_style.reference.is_none()}>{"none"}</option>
+                            {for props.body_labels.iter().map(|body_label| html! {
+                                <option selected={settings.trail_style.reference == Some(body_label.id)}>{&body_label.name}</option>
+                            })}
+                        </select>
+                    }
+                    <label for="input_trail_faded" class="whitespace-nowrap">{"fade"}</label>
+                    <input id="input_trail_faded" type="checkbox" class="accent-white" checked={settings.trail_style.faded} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                trail_style: TrailStyle {
+                                    faded: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                    ..settings.trail_style
+                                },
+                                ..settings
+                            }
+                        })}/>
+                    <label for="input_trail_width" class="whitespace-nowrap">{"width by"}</label>
+                    <select id="input_trail_width" class="bg-neutral-800 py-1 px-3 border border-neutral-500 rounded" onchange={props.set_settings_callback.reform(move |e: Event| {
+                            let index = e.target().unwrap().unchecked_into::<HtmlSelectElement>().selected_index();
+                            Settings {
+                                trail_style: TrailStyle {
+                                    width: usize::try_from(index).ok()
+                                        .and_then(|index| TrailWidth::ALL.get(index).copied())
+                                        .unwrap_or(settings.trail_style.width),
+                                    ..settings.trail_style
+                                },
+                                ..settings
+                            }
+                        })}>
+                        {for TrailWidth::ALL.iter().map(|width| html! {
+                            <option selected={settings.trail_style.width == *width}>{width.name()}</option>
+                        })}
+                    </select>
+                </div>
+                <div class="flex flex-row gap-3 items-center">
+                    <label for="input_performance_shown" class="whitespace-nowrap">{"performance"}</label>
+                    <input id="input_performance_shown" type="checkbox" class="accent-white" checked={settings.performance_shown} oninput={props.set_settings_callback.reform(move |e: InputEvent| {
+                            Settings {
+                                performance_shown: e.target().unwrap().unchecked_into::<HtmlInputElement>().checked(),
+                                ..settings
+                            }
+                        })}/>
+                </div>
+            </div>
         </>
     }
 }
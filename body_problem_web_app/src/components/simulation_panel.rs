@@ -1,128 +1,228 @@
 use std::time::Duration;
 
 use nalgebra::Vector2;
-use web_sys::MouseEvent;
-use yew::{function_component, html, use_state, Callback, ContextProvider, Html};
-use yew_agent::prelude::{use_reactor_subscription, UseReactorSubscriptionHandle};
-use yew_hooks::{use_effect_once, use_window_size};
+use web_sys::{HtmlElement, KeyboardEvent, MouseEvent};
+use web_sys::wasm_bindgen::JsCast;
+use yew::{function_component, html, use_effect_with, use_mut_ref, use_state, Callback, ContextProvider, Html};
+use yew::platform::spawn_local;
+use yew_agent::prelude::{use_oneshot_runner, use_reactor_subscription, UseReactorSubscriptionHandle};
+use yew_hooks::{use_effect_once, use_event_with_window, use_window_size};
 
 use body_problem::Body;
+use body_problem::generator::Generator;
+use body_problem::labeled::{BodyId, BodyLabel};
 
-use crate::agents::simulation_reactor::{SimulationReactor, SimulationReactorInstruction, SimulationState};
+use crate::agents::forecast_oneshot::{ForecastInput, ForecastOneshot};
+use crate::agents::simulation_reactor::{SimulationCommand, SimulationEdits, SimulationReactor, SimulationResponse};
 use crate::components::body_canvas::BodyCanvas;
 use crate::components::body_table::BodyTable;
+use crate::components::conic_canvas::ConicCanvas;
+use crate::components::energy_plot::EnergyPlot;
 use crate::components::energy_sum_table::EnergySumTable;
+use crate::components::field_canvas::FieldCanvas;
+use crate::components::forecast_canvas::ForecastCanvas;
+use crate::components::performance_hud::PerformanceHud;
+use crate::components::preset_picker::PresetPicker;
+use crate::components::session_slots::SessionSlots;
 use crate::components::simulation_controls::SimulationControls;
 use crate::components::trajectory_canvas::TrajectoryCanvas;
+use crate::components::webgl_canvas::WebGlCanvas;
+use crate::models::edit_history::{EditCoalescing, EditHistory};
+use crate::models::latest_simulation_responses::LatestSimulationResponses;
+use crate::models::preset::Preset;
 use crate::models::rendered_body::RenderedBody;
+use crate::models::rendered_simulation_state::RenderedSimulationState;
+use crate::models::renderer::Renderer;
+use crate::models::session::Session;
 use crate::models::settings::Settings;
+use crate::utils::download;
+use crate::utils::webgl_renderer::webgl2_supported;
 
-const SETTINGS_DEFAULT: Settings = Settings::new(Duration::from_secs(5), 1f64, 0.0001f64, true);
+// about a second at the default frame rate
+const PERFORMANCE_STATISTICS_FRAMES: usize = 50;
 
-#[derive(Clone)]
-pub(crate) struct RenderedSimulationState {
-    pub(crate) rendered_bodies: Vec<RenderedBody>,
-    pub(crate) duration_elapsed_total: Duration,
-}
-
-impl RenderedSimulationState {
-    pub fn new(rendered_bodies: Vec<RenderedBody>, duration_elapsed_total: Duration) -> Self {
-        Self { rendered_bodies, duration_elapsed_total }
-    }
-}
-
-impl PartialEq for RenderedSimulationState {
-    fn eq(&self, other: &Self) -> bool {
-        self.rendered_bodies.iter().eq(other.rendered_bodies.iter())
-            && self.duration_elapsed_total == other.duration_elapsed_total
-    }
-}
-
-impl From<RenderedSimulationState> for SimulationState {
-    fn from(rendered_simulation_state: RenderedSimulationState) -> Self {
-        Self::new(rendered_simulation_state.rendered_bodies.iter().map(|b| b.body.clone()).collect(), rendered_simulation_state.duration_elapsed_total)
-    }
-}
-
-impl PartialEq<RenderedSimulationState> for SimulationState {
-    fn eq(&self, other: &RenderedSimulationState) -> bool {
-        self.bodies.iter().eq(other.rendered_bodies.iter().map(|b| &b.body))
-    }
-}
+// cycled through by the generated bodies
+const GENERATED_BODY_COLORS: [&str; 6] = ["#ffff3f", "#5a8cc8", "#bfbfbf", "#e0703c", "#6cc070", "#c070c0"];
 
 #[function_component(SimulationPanel)]
 pub fn simulation_panel() -> Html {
-    let rendered_state = use_state(|| RenderedSimulationState::new(vec![
-        RenderedBody::new(0, Body::new(1e17, Vector2::new(0f64, 0f64), Vector2::new(0f64, -1.52f64)), "#ffff3f".to_string()),
-        RenderedBody::new(1, Body::new(1e15, Vector2::new(300f64, 0f64), Vector2::new(0f64, 149.76f64)), "#5a8cc8".to_string()),
-        RenderedBody::new(2, Body::new(1e13, Vector2::new(320f64, 0f64), Vector2::new(0f64, 206.92f64)), "#bfbfbf".to_string()),
-    ], Duration::ZERO));
+    let session_last = use_state(Session::load_last);
+    let rendered_state = use_state(|| match &*session_last {
+        Some(session_last) => session_last.rendered_state.clone(),
+        None => Preset::default().rendered_state(),
+    });
     let rendered_state_after_last_edit = use_state(|| (*rendered_state).clone());
     let rendered_state_edited_this_pause = use_state(|| false);
     let simulation_paused = use_state(|| false);
-    let simulation_reset = use_state(|| false);
-    let settings = use_state(|| SETTINGS_DEFAULT);
+    // the state of the agent's timeline the current pause started at, to which the edits are relative
+    let rendered_state_base = use_state(|| None::<RenderedSimulationState>);
+    let edit_history = use_state(EditHistory::default);
+    let selected_body = use_state(|| None::<BodyId>);
+    let webgl2_supported = use_state(webgl2_supported);
+    let settings = use_state(|| match &*session_last {
+        Some(session_last) => session_last.settings.clone(),
+        None => Settings::default(),
+    });
+
+    // An edit made during the current pause is persisted right away, not only after resuming.
+    let session = Session::new(
+        if *rendered_state_edited_this_pause {
+            (*rendered_state).clone()
+        } else {
+            (*rendered_state_after_last_edit).clone()
+        },
+        (*settings).clone(),
+    );
+
+    use_effect_with(
+        session.clone(),
+        |session| session.store_last(),
+    );
 
     let simulation_agent: UseReactorSubscriptionHandle<SimulationReactor> = use_reactor_subscription::<SimulationReactor>();
+    // the number of the sent commands replacing the state, to tell the outdated states sent by the agent apart
+    let simulation_revision = use_mut_ref(|| 0u64);
+    let simulation_responses_handled = use_mut_ref(|| 0usize);
 
-    {
+    let send_command = {
         let simulation_agent = simulation_agent.clone();
+        let simulation_revision = simulation_revision.clone();
+        Callback::from(move |command: SimulationCommand| {
+            if command.replaces_state() {
+                *simulation_revision.borrow_mut() += 1;
+            }
+            simulation_agent.send(command);
+        })
+    };
+
+    {
+        let send_command = send_command.clone();
         let rendered_state = rendered_state.clone();
         let settings = settings.clone();
         use_effect_once(move || {
-            simulation_agent.send(Some(SimulationReactorInstruction::new(
-                Some((*rendered_state).clone().into()),
-                settings.simulation_speed,
-            )));
+            send_settings(&send_command, &settings, None);
+            send_command.emit(SimulationCommand::Start((*rendered_state).clone().into()));
             || ()
         });
     }
 
-    let rendered_state_new = if *simulation_paused {
-        (*rendered_state).clone()
-    } else {
-        let state_new = simulation_agent.last()
-            .map(|state| state.as_ref().as_ref()).unwrap_or_default();
-
-        // The simulation was reset, and we are waiting for the agent to send the reset bodies.
-        if *simulation_reset {
-            if let Some(state_new) = state_new {
-                if state_new == &*rendered_state_after_last_edit {
-                    simulation_reset.set(false);
+    {
+        let rendered_state = rendered_state.clone();
+        let rendered_state_base = rendered_state_base.clone();
+        let simulation_agent = simulation_agent.clone();
+        let simulation_revision = simulation_revision.clone();
+        let simulation_paused = *simulation_paused;
+        let rendered_state_edited_this_pause = *rendered_state_edited_this_pause;
+        use_effect_with(
+            simulation_agent.len(),
+            move |_| {
+                let responses_new = simulation_agent.iter().skip(*simulation_responses_handled.borrow());
+                // While paused, the state the agent stopped at or moved to (e.g. by seeking) is taken over unless
+                // the bodies were edited meanwhile.
+                let state_taken_over = responses_new.clone()
+                    .rev()
+                    .find_map(|response| match response.as_ref() {
+                        SimulationResponse::State(output) if output.revision == *simulation_revision.borrow() => {
+                            Some(output.states.last())
+                        }
+                        SimulationResponse::Paused { state, revision } if *revision == *simulation_revision.borrow() => {
+                            Some(state.clone())
+                        }
+                        _ => None,
+                    })
+                    .filter(|_| simulation_paused && !rendered_state_edited_this_pause);
+                if let Some(state) = state_taken_over {
+                    let rendered_state_new = rendered_state.with_simulation_state(&state);
+                    rendered_state.set(rendered_state_new.clone());
+                    rendered_state_base.set(Some(rendered_state_new));
                 }
-            }
 
-            (*rendered_state_after_last_edit).clone()
-        } else {
-            match state_new {
-                None => (*rendered_state).clone(),
-                Some(state_new) => RenderedSimulationState::new(
-                    state_new.bodies.iter()
-                        .enumerate()
-                        .map(|(index, body)| {
-                            RenderedBody {
-                                index,
-                                body: body.clone(),
-                                potential_energy: state_new.bodies.iter()
-                                    .enumerate()
-                                    .filter(|(index2, _)| index != *index2)
-                                    .map(|(_, body2)| body.potential_energy_to(body2))
-                                    .sum(),
-                                color: rendered_state.rendered_bodies[index].color.clone(),
+                for response in responses_new {
+                    match response.as_ref() {
+                        SimulationResponse::Error(message) => log::error!("the simulation agent failed: {message}"),
+                        SimulationResponse::Recording(format, data) => {
+                            if let Err(error) = download(&format!("trajectory.{}", format.extension()), format.media_type(), data) {
+                                log::error!("failed to download the trajectory: {error:?}");
                             }
-                        }).collect(), state_new.duration_elapsed_total)
-            }
-        }
+                        }
+                        _ => {}
+                    }
+                }
+                *simulation_responses_handled.borrow_mut() = simulation_agent.len();
+            },
+        );
+    }
+
+    // the bodies the paths were predicted for and the paths
+    let forecast = use_state(|| None::<(Vec<Body>, Vec<Vec<Vector2<f64>>>)>);
+    let forecast_runner = use_oneshot_runner::<ForecastOneshot>();
+    // the number of the latest forecast request, which tells the outdated results apart
+    let forecast_request = use_mut_ref(|| 0u64);
+
+    {
+        let forecast = forecast.clone();
+        let bodies = rendered_state.rendered_bodies.iter()
+            .map(|rendered_body| rendered_body.body.clone())
+            .collect::<Vec<Body>>();
+        use_effect_with(
+            (bodies, *simulation_paused, settings.forecast_duration, settings.integration),
+            move |(bodies, simulation_paused, forecast_duration, integration)| {
+                *forecast_request.borrow_mut() += 1;
+                let request = *forecast_request.borrow();
+                if *simulation_paused && !forecast_duration.is_zero() {
+                    let input = ForecastInput::new(
+                        bodies.clone(),
+                        *forecast_duration,
+                        integration.integrator,
+                        integration.step,
+                    );
+                    spawn_local(async move {
+                        let bodies = input.bodies.clone();
+                        let paths = forecast_runner.run(input).await;
+                        if *forecast_request.borrow() == request {
+                            forecast.set(Some((bodies, paths)));
+                        }
+                    });
+                } else {
+                    forecast.set(None);
+                }
+            },
+        );
+    }
+
+    let latest_simulation_responses = use_mut_ref(LatestSimulationResponses::default);
+    latest_simulation_responses.borrow_mut().update(&simulation_agent, PERFORMANCE_STATISTICS_FRAMES);
+    let latest_simulation_responses = latest_simulation_responses.borrow();
+
+    // the latest states of the current revision, unless paused since
+    let output_last = latest_simulation_responses.output(*simulation_revision.borrow())
+        .filter(|_| !*simulation_paused);
+    let duration_recorded = latest_simulation_responses.duration_recorded();
+    let recording_status = latest_simulation_responses.recording_status();
+
+    let frame_statistics = if settings.performance_shown {
+        latest_simulation_responses.frame_statistics()
+    } else {
+        Vec::new()
     };
 
+    let rendered_state_new = match output_last {
+        Some(output) => rendered_state.with_simulation_state(&output.states.last()),
+        None => (*rendered_state).clone(),
+    };
+
+    let segments_intermediate = output_last
+        .map(|output| output.states.segments_intermediate())
+        .unwrap_or_default();
+
     let toggle_pause_callback = {
         let rendered_state = rendered_state.clone();
         let rendered_state_after_last_edit = rendered_state_after_last_edit.clone();
         let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let rendered_state_base = rendered_state_base.clone();
         let simulation_paused = simulation_paused.clone();
         let rendered_state_new = rendered_state_new.clone();
-        let simulation_agent = simulation_agent.clone();
-        let settings = settings.clone();
+        let send_command = send_command.clone();
 
         Callback::from(
             move |_| {
@@ -131,16 +231,25 @@ pub fn simulation_panel() -> Html {
 
                 if simulation_paused_new {
                     rendered_state.set(rendered_state_new.clone());
-                    simulation_agent.send(None);
+                    rendered_state_base.set(Some(rendered_state_new.clone()));
+                    send_command.emit(SimulationCommand::Pause);
                 } else {
                     if *rendered_state_edited_this_pause {
                         rendered_state_after_last_edit.set((*rendered_state).clone());
                         rendered_state_edited_this_pause.set(false);
+
+                        // Only the changes are sent if the agent knows the state they were made to.
+                        let edits = (*rendered_state_base).clone()
+                            .and_then(|rendered_state_base| SimulationEdits::between(
+                                &rendered_state_base.into(),
+                                &rendered_state_new.clone().into(),
+                            ));
+                        send_command.emit(match edits {
+                            Some(edits) => SimulationCommand::Edit(edits),
+                            None => SimulationCommand::SetState(rendered_state_new.clone().into()),
+                        });
                     }
-                    simulation_agent.send(Some(SimulationReactorInstruction::new(
-                        Some(rendered_state_new.clone().into()),
-                        settings.simulation_speed))
-                    );
+                    send_command.emit(SimulationCommand::Resume);
                 }
             }
         )
@@ -149,44 +258,106 @@ pub fn simulation_panel() -> Html {
     let reset_callback = {
         let rendered_state = rendered_state.clone();
         let rendered_state_after_last_edit = rendered_state_after_last_edit.clone();
-        let simulation_paused = simulation_paused.clone();
-        let simulation_agent = simulation_agent.clone();
-        let simulation_reset = simulation_reset.clone();
-        let settings = settings.clone();
+        let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let send_command = send_command.clone();
 
         Callback::from(
             move |_| {
-                simulation_reset.set(true);
-                if *simulation_paused {
-                    rendered_state.set((*rendered_state_after_last_edit).clone());
-                } else {
-                    simulation_agent.send(Some(SimulationReactorInstruction::new(
-                        Some((*rendered_state_after_last_edit).clone().into()),
-                        settings.simulation_speed)));
-                }
+                rendered_state.set((*rendered_state_after_last_edit).clone());
+                rendered_state_edited_this_pause.set(false);
+                send_command.emit(SimulationCommand::SetState((*rendered_state_after_last_edit).clone().into()));
             }
         )
     };
 
+    let seek_callback = send_command.reform(SimulationCommand::Seek);
+
+    let step_callback = send_command.reform(SimulationCommand::Step);
+
+    let record_callback = send_command.reform(|recording| if recording {
+        SimulationCommand::StartRecording
+    } else {
+        SimulationCommand::StopRecording
+    });
+
+    let export_callback = {
+        let labels = rendered_state_new.rendered_bodies.iter()
+            .map(|rendered_body| rendered_body.label.clone())
+            .collect::<Vec<_>>();
+        send_command.reform(move |format| SimulationCommand::ExportRecording(format, labels.clone()))
+    };
+
     let body_add_callback = {
         let rendered_state = rendered_state.clone();
+        let rendered_state_new = rendered_state_new.clone();
         let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
         let simulation_paused = simulation_paused.clone();
         let toggle_pause_callback = toggle_pause_callback.clone();
+        let edit_history = edit_history.clone();
 
         Callback::from(
             move |_| {
-                if !*simulation_paused {
+                let rendered_state_current = if *simulation_paused {
+                    (*rendered_state).clone()
+                } else {
                     toggle_pause_callback.emit(MouseEvent::new("").unwrap());
-                }
+                    rendered_state_new.clone()
+                };
+
+                let mut edit_history_new = (*edit_history).clone();
+                edit_history_new.record(rendered_state_current.clone(), None);
+                edit_history.set(edit_history_new);
+
+                let mut rendered_state_new = rendered_state_current;
+                let id = rendered_state_new.body_id_unused();
+                rendered_state_new.rendered_bodies.push(RenderedBody::new(
+                    rendered_state_new.rendered_bodies.len(),
+                    BodyLabel::new(id, format!("body {}", id.0), None),
+                    Body::new(1f64, Vector2::new(0f64, 0f64), Vector2::new(0f64, 0f64)),
+                    "#ffffff".to_string(),
+                ));
+                rendered_state_new.duration_elapsed_total = Duration::ZERO;
 
-                let mut rendered_state_new = (*rendered_state).clone();
-                rendered_state_new.rendered_bodies.push(RenderedBody {
-                    index: rendered_state_new.rendered_bodies.len(),
-                    body: Body::new(1f64, Vector2::new(0f64, 0f64), Vector2::new(0f64, 0f64)),
-                    potential_energy: 0f64,
-                    color: "#ffffff".to_string(),
-                });
+                rendered_state.set(rendered_state_new);
+                rendered_state_edited_this_pause.set(true);
+            }
+        )
+    };
+
+    let bodies_generate_callback = {
+        let rendered_state = rendered_state.clone();
+        let rendered_state_new = rendered_state_new.clone();
+        let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let simulation_paused = simulation_paused.clone();
+        let toggle_pause_callback = toggle_pause_callback.clone();
+        let edit_history = edit_history.clone();
+
+        Callback::from(
+            move |(generator, replacing): (Generator, bool)| {
+                let rendered_state_current = if *simulation_paused {
+                    (*rendered_state).clone()
+                } else {
+                    toggle_pause_callback.emit(MouseEvent::new("").unwrap());
+                    rendered_state_new.clone()
+                };
+
+                let mut edit_history_new = (*edit_history).clone();
+                edit_history_new.record(rendered_state_current.clone(), None);
+                edit_history.set(edit_history_new);
+
+                let mut rendered_state_new = rendered_state_current;
+                if replacing {
+                    rendered_state_new.rendered_bodies.clear();
+                }
+                for (index, body) in generator.generate().into_iter().enumerate() {
+                    let id = rendered_state_new.body_id_unused();
+                    rendered_state_new.rendered_bodies.push(RenderedBody::new(
+                        rendered_state_new.rendered_bodies.len(),
+                        BodyLabel::new(id, format!("body {}", id.0), None),
+                        body,
+                        GENERATED_BODY_COLORS[index % GENERATED_BODY_COLORS.len()].to_string(),
+                    ));
+                }
                 rendered_state_new.duration_elapsed_total = Duration::ZERO;
 
                 rendered_state.set(rendered_state_new);
@@ -198,17 +369,30 @@ pub fn simulation_panel() -> Html {
     let body_edit_callback = {
         let rendered_state = rendered_state.clone();
         let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let edit_history = edit_history.clone();
 
         Callback::from(
             move |rendered_body: RenderedBody| {
                 let mut rendered_state_new = (*rendered_state).clone();
-                let index = rendered_body.index;
+                let Some(index) = rendered_state_new.rendered_bodies.iter()
+                    .position(|rendered_body_old| rendered_body_old.label.id == rendered_body.label.id) else {
+                    return;
+                };
                 /* important for preserving the `rendered_bodies_last_edit` when the user just
                    clicks into an input or edits a body to the same value as before */
                 if rendered_state_new.rendered_bodies[index] == rendered_body {
                     return;
                 }
 
+                let color_edited_only = RenderedBody {
+                    color: rendered_body.color.clone(),
+                    ..rendered_state_new.rendered_bodies[index].clone()
+                } == rendered_body;
+                let coalescing = color_edited_only.then_some(EditCoalescing::Color { id: rendered_body.label.id });
+                let mut edit_history_new = (*edit_history).clone();
+                edit_history_new.record((*rendered_state).clone(), coalescing);
+                edit_history.set(edit_history_new);
+
                 rendered_state_new.rendered_bodies[index] = rendered_body;
                 rendered_state_new.duration_elapsed_total = Duration::ZERO;
 
@@ -221,10 +405,20 @@ pub fn simulation_panel() -> Html {
     let body_remove_callback = {
         let rendered_state = rendered_state.clone();
         let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let edit_history = edit_history.clone();
 
         Callback::from(
-            move |index: usize| {
+            move |id: BodyId| {
                 let mut rendered_state_new = (*rendered_state).clone();
+                let Some(index) = rendered_state_new.rendered_bodies.iter()
+                    .position(|rendered_body| rendered_body.label.id == id) else {
+                    return;
+                };
+
+                let mut edit_history_new = (*edit_history).clone();
+                edit_history_new.record((*rendered_state).clone(), None);
+                edit_history.set(edit_history_new);
+
                 rendered_state_new.rendered_bodies.remove(index);
                 for (index, rendered_body) in rendered_state_new.rendered_bodies.iter_mut()
                     .enumerate() {
@@ -238,41 +432,189 @@ pub fn simulation_panel() -> Html {
         )
     };
 
+    let edit_history_step_callback = {
+        let rendered_state = rendered_state.clone();
+        let rendered_state_new = rendered_state_new.clone();
+        let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let simulation_paused = simulation_paused.clone();
+        let toggle_pause_callback = toggle_pause_callback.clone();
+        let edit_history = edit_history.clone();
+
+        Callback::from(
+            move |redo: bool| {
+                let rendered_state_current = if *simulation_paused {
+                    (*rendered_state).clone()
+                } else {
+                    rendered_state_new.clone()
+                };
+
+                let mut edit_history_new = (*edit_history).clone();
+                let rendered_state_restored = if redo {
+                    edit_history_new.redo(rendered_state_current)
+                } else {
+                    edit_history_new.undo(rendered_state_current)
+                };
+
+                if let Some(rendered_state_restored) = rendered_state_restored {
+                    if !*simulation_paused {
+                        toggle_pause_callback.emit(MouseEvent::new("").unwrap());
+                    }
+
+                    rendered_state.set(rendered_state_restored);
+                    rendered_state_edited_this_pause.set(true);
+                    edit_history.set(edit_history_new);
+                }
+            }
+        )
+    };
+    let undo_callback = edit_history_step_callback.reform(|_| false);
+    let redo_callback = edit_history_step_callback.reform(|_| true);
+
+    {
+        let edit_history_step_callback = edit_history_step_callback.clone();
+        use_event_with_window("keydown", move |e: KeyboardEvent| {
+            if !(e.ctrl_key() || e.meta_key()) || !e.key().eq_ignore_ascii_case("z") {
+                return;
+            }
+
+            // Text fields have their own undo history, and the shortcut would change a focused select.
+            let target_is_field = e.target()
+                .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+                .is_some_and(|target| target.is_content_editable()
+                    || ["input", "select", "textarea"].iter().any(|tag| target.tag_name().eq_ignore_ascii_case(tag)));
+            if target_is_field {
+                return;
+            }
+
+            e.prevent_default();
+            edit_history_step_callback.emit(e.shift_key());
+        });
+    }
+
+    let session_load_callback = {
+        let rendered_state = rendered_state.clone();
+        let rendered_state_new = rendered_state_new.clone();
+        let rendered_state_after_last_edit = rendered_state_after_last_edit.clone();
+        let rendered_state_edited_this_pause = rendered_state_edited_this_pause.clone();
+        let simulation_paused = simulation_paused.clone();
+        let send_command = send_command.clone();
+        let settings = settings.clone();
+        let edit_history = edit_history.clone();
+
+        Callback::from(
+            move |session: Session| {
+                let mut edit_history_new = (*edit_history).clone();
+                edit_history_new.record(if *simulation_paused {
+                    (*rendered_state).clone()
+                } else {
+                    rendered_state_new.clone()
+                }, None);
+                edit_history.set(edit_history_new);
+
+                // Loading behaves like a reset to the loaded state.
+                rendered_state.set(session.rendered_state.clone());
+                rendered_state_after_last_edit.set(session.rendered_state.clone());
+                rendered_state_edited_this_pause.set(false);
+                send_settings(&send_command, &session.settings, Some(&*settings));
+                send_command.emit(SimulationCommand::SetState(session.rendered_state.into()));
+                settings.set(session.settings);
+            }
+        )
+    };
+
+    // Loading a preset behaves like loading a session with the current settings.
+    let preset_load_callback = {
+        let settings = settings.clone();
+        session_load_callback.reform(move |preset: Preset| Session::new(preset.rendered_state(), (*settings).clone()))
+    };
+
     let set_settings_callback = {
         let settings = settings.clone();
+        let send_command = send_command.clone();
 
         Callback::from(
             move |settings_new: Settings| {
-                if settings_new.simulation_speed != settings.simulation_speed {
-                    simulation_agent.send(Some(SimulationReactorInstruction::new(
-                        None,
-                        settings_new.simulation_speed,
-                    )));
-                }
-
+                send_settings(&send_command, &settings_new, Some(&*settings));
                 settings.set(settings_new);
             }
         )
     };
 
+    let select_body_callback = {
+        let selected_body = selected_body.clone();
+
+        Callback::from(
+            move |id: Option<BodyId>| selected_body.set(id)
+        )
+    };
+
+    // The 2D canvas is the fallback for browsers without WebGL 2.
+    let webgl_used = settings.renderer == Renderer::WebGl2 && *webgl2_supported;
+
     let window_size = use_window_size();
     html! {
         <ContextProvider<Settings> context={(*settings).clone()}>
-            <div style={format!("height: {}px", (window_size.1 - 150f64).max(0f64))}>
-                <TrajectoryCanvas rendered_state={rendered_state_new.clone()}
-                    rendered_bodies_edited_this_pause={*rendered_state_edited_this_pause}
-                    simulation_paused={*simulation_paused} simulation_reset={*simulation_reset}/>
-                <BodyCanvas rendered_bodies={rendered_state_new.rendered_bodies.clone()}/>
+            <div class="relative" style={format!("height: {}px", (window_size.1 - 150f64).max(0f64))}>
+                <FieldCanvas rendered_bodies={rendered_state_new.rendered_bodies.clone()} simulation_paused={*simulation_paused}/>
+                if webgl_used {
+                    <WebGlCanvas rendered_state={rendered_state_new.clone()} segments_intermediate={segments_intermediate.clone()}
+                        rendered_bodies_edited_this_pause={*rendered_state_edited_this_pause}
+                        simulation_paused={*simulation_paused} simulation_revision={*simulation_revision.borrow()}/>
+                } else {
+                    <TrajectoryCanvas rendered_state={rendered_state_new.clone()} {segments_intermediate}
+                        rendered_bodies_edited_this_pause={*rendered_state_edited_this_pause}
+                        simulation_paused={*simulation_paused} simulation_revision={*simulation_revision.borrow()}
+                        selected_body={*selected_body}/>
+                    <ConicCanvas rendered_bodies={rendered_state_new.rendered_bodies.clone()} primary={settings.conic_primary}/>
+                }
+                <ForecastCanvas rendered_bodies={rendered_state_new.rendered_bodies.clone()}
+                    paths={(*forecast).as_ref()
+                        // a forecast for bodies edited since would be misleading
+                        .filter(|(bodies, _)| *simulation_paused && bodies.iter()
+                            .eq(rendered_state_new.rendered_bodies.iter().map(|rendered_body| &rendered_body.body)))
+                        .map(|(_, paths)| paths.clone())}/>
+                <BodyCanvas rendered_bodies={rendered_state_new.rendered_bodies.clone()} bodies_shown={!webgl_used}
+                    selected_body={*selected_body} select_callback={select_body_callback}/>
+                if settings.performance_shown {
+                    <PerformanceHud statistics={frame_statistics}/>
+                }
             </div>
             <section class="p-4 flex flex-col gap-8">
                 <SimulationControls simulation_paused={*simulation_paused} {toggle_pause_callback}
-                    {reset_callback} {set_settings_callback}
-                    duration_elapsed_total={rendered_state_new.duration_elapsed_total}/>
+                    {reset_callback} {set_settings_callback} {seek_callback} {step_callback} {record_callback} {export_callback}
+                    {recording_status} seek_allowed={!*rendered_state_edited_this_pause}
+                    duration_elapsed_total={rendered_state_new.duration_elapsed_total}
+                    {duration_recorded}
+                    body_labels={rendered_state_new.rendered_bodies.iter().map(|rendered_body| rendered_body.label.clone()).collect::<Vec<_>>()}/>
                 <BodyTable rendered_bodies={rendered_state_new.rendered_bodies.clone()}
                     edit_allowed={*simulation_paused} add_callback={body_add_callback}
-                    edit_callback={body_edit_callback} remove_callback={body_remove_callback}/>
-                <EnergySumTable rendered_bodies={rendered_state_new.rendered_bodies}/>
+                    generate_callback={bodies_generate_callback}
+                    edit_callback={body_edit_callback} remove_callback={body_remove_callback}
+                    {undo_callback} {redo_callback} selected_body={*selected_body}
+                    undo_allowed={edit_history.can_undo()} redo_allowed={edit_history.can_redo()}/>
+                <EnergySumTable rendered_bodies={rendered_state_new.rendered_bodies.clone()}/>
+                <EnergyPlot rendered_state={rendered_state_new}/>
+                <PresetPicker load_callback={preset_load_callback}/>
+                <SessionSlots {session} load_callback={session_load_callback}/>
             </section>
         </ContextProvider<Settings>>
     }
 }
+
+/// Sends the settings the agent uses, only those changed since the previous ones if any.
+fn send_settings(send_command: &Callback<SimulationCommand>, settings: &Settings, settings_previous: Option<&Settings>) {
+    let changed = |value: fn(&Settings) -> f64| settings_previous.is_none_or(|previous| value(previous) != value(settings));
+
+    if changed(|settings| settings.simulation_speed) {
+        send_command.emit(SimulationCommand::SetSpeed(settings.simulation_speed));
+    }
+    if settings_previous.is_none_or(|previous| previous.integration.integrator != settings.integration.integrator) {
+        send_command.emit(SimulationCommand::SetIntegrator(settings.integration.integrator));
+    }
+    if changed(|settings| settings.integration.step) {
+        send_command.emit(SimulationCommand::SetStep(settings.integration.step));
+    }
+    if changed(|settings| settings.integration.target_fps) {
+        send_command.emit(SimulationCommand::SetTargetFps(settings.integration.target_fps));
+    }
+}
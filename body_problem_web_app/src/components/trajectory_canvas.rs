@@ -1,21 +1,36 @@
-use std::collections::VecDeque;
+use std::collections::BTreeMap;
 
-use crate::components::simulation_panel::RenderedSimulationState;
+use body_problem::labeled::BodyId;
+
+use crate::models::rendered_simulation_state::RenderedSimulationState;
 use crate::models::settings::Settings;
+use crate::models::trail_style::{TrailColoring, TrailStyle, TrailWidth};
 use crate::models::trajectory_segment::TrajectorySegment;
 use crate::utils::{CanvasClear, SimulationCanvasInitialize};
+use crate::utils::trajectories::{Trajectories, Trajectory, TrajectoryPoint};
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 use web_time::Duration;
-use yew::{function_component, html, use_context, use_effect_with, use_node_ref, use_state, Html, Properties};
+use yew::{function_component, html, use_context, use_effect_with, use_mut_ref, use_node_ref, use_state, Html, Properties};
 
-const TRAJECTORY_MAX_SEGMENT_LENGTH: f64 = 0.5f64;
+const TRAJECTORY_LINE_WIDTH: f64 = 1f64;
+const TRAJECTORY_SELECTED_LINE_WIDTH: f64 = 3f64;
+// how much wider than the base width the trails of variable width get at most
+const TRAJECTORY_WIDTH_VARIATION: f64 = 2f64;
+/// The number of steps the colours, opacities and widths of the styled trails are rounded to, so that the lines of
+/// the same style are stroked together.
+const TRAIL_STYLE_LEVELS: f64 = 16f64;
 
 #[derive(Properties, PartialEq)]
 pub struct TrajectoryCanvasProps {
     pub(crate) rendered_state: RenderedSimulationState,
+    /// The trajectory segments between the previous rendered state and the current one.
+    pub(crate) segments_intermediate: Vec<TrajectorySegment>,
     pub(crate) rendered_bodies_edited_this_pause: bool,
     pub(crate) simulation_paused: bool,
-    pub(crate) simulation_reset: bool,
+    /// Changes when the agent's state is replaced (e.g. by a reset), which starts the trajectories over.
+    pub(crate) simulation_revision: u64,
+    /// The body whose trajectory is highlighted.
+    pub(crate) selected_body: Option<BodyId>,
 }
 
 #[function_component(TrajectoryCanvas)]
@@ -23,15 +38,10 @@ pub fn trajectory_canvas(props: &TrajectoryCanvasProps) -> Html {
     let canvas_ref = use_node_ref();
     let canvas = canvas_ref.cast::<HtmlCanvasElement>();
     let context = use_state(|| None);
-    let trajectory_segments = use_state(
-        || VecDeque::from([
-            TrajectorySegment::new(
-                props.rendered_state.rendered_bodies.iter()
-                    .map(|rendered_body| rendered_body.body.position)
-                    .collect(),
-                props.rendered_state.duration_elapsed_total)
-        ]));
+    let trajectories = use_mut_ref(Trajectories::new);
     let settings = use_context::<Settings>().unwrap();
+    // the selected body, the trail style and the duration of the trajectories last drawn
+    let drawn = use_mut_ref(|| None::<(Option<BodyId>, TrailStyle, Duration)>);
 
     {
         let context = context.clone();
@@ -42,7 +52,8 @@ pub fn trajectory_canvas(props: &TrajectoryCanvasProps) -> Html {
                 let mut resize_listener = None;
                 if let Some(canvas) = canvas {
                     let canvas: HtmlCanvasElement = canvas.clone();
-                    resize_listener = Some(canvas.initialize_for_simulation(context, false));
+                    // transparent to show the field layer beneath
+                    resize_listener = Some(canvas.initialize_for_simulation(context, true));
                 }
 
                 move || drop(resize_listener)
@@ -51,10 +62,10 @@ pub fn trajectory_canvas(props: &TrajectoryCanvasProps) -> Html {
     }
 
     let reset = {
-        let body_positions = trajectory_segments.clone();
+        let trajectories = trajectories.clone();
         let context = (*context).clone();
         move || {
-            body_positions.set(VecDeque::new());
+            trajectories.borrow_mut().clear();
             if let Some(context) = context {
                 context.clear().unwrap();
             }
@@ -76,60 +87,82 @@ pub fn trajectory_canvas(props: &TrajectoryCanvasProps) -> Html {
     {
         let reset = reset.clone();
         use_effect_with(
-            props.simulation_reset,
-            move |&simulation_reset| {
-                if simulation_reset {
-                    reset();
-                }
-            },
+            props.simulation_revision,
+            move |_| reset(),
         );
     }
 
     if let (Some(context), Some(_)) = ((*context).clone(), canvas) {
         let context: CanvasRenderingContext2d = context;
+        let mut trajectories = trajectories.borrow_mut();
+
+        // The time went back (e.g. by seeking), so the recorded trajectories no longer precede it.
+        if trajectories.recorded_after_last().is_some_and(|recorded_after_last|
+            recorded_after_last > props.rendered_state.duration_elapsed_total) {
+            trajectories.clear();
+        }
+
+        let mut trajectories_changed = false;
+
+        if !props.simulation_paused {
+            // Those already recorded are skipped, e.g. when rendering again without a new state.
+            let recorded_after_last = trajectories.recorded_after_last();
+            let segments_new = props.segments_intermediate.iter()
+                .filter(|segment| recorded_after_last.is_none_or(|last| segment.recorded_after > last))
+                .cloned()
+                .chain([TrajectorySegment::new(&props.rendered_state.bodies(), props.rendered_state.duration_elapsed_total)])
+                .filter(|segment| recorded_after_last.is_none_or(|last| segment.recorded_after > last));
+
+            for segment in segments_new {
+                trajectories_changed |= trajectories.record(&segment);
+            }
+
+            trajectories_changed |= trajectories.trim(props.rendered_state.duration_elapsed_total.saturating_sub(settings.trajectory_duration));
+        }
+
+        // The selection and the style may change while paused, when no segments are added.
+        let drawn_new = (props.selected_body, settings.trail_style, settings.trajectory_duration);
+        let drawn_changed = *drawn.borrow() != Some(drawn_new);
 
         if settings.trajectory_duration <= Duration::ZERO {
             context.clear().unwrap();
-        } else if !props.simulation_paused
-            && !props.simulation_reset
-            && ((*trajectory_segments).is_empty()
-            || props.rendered_state.rendered_bodies.iter().any(|rendered_body|
-            (rendered_body.body.position - (*trajectory_segments).iter().last().unwrap()
-                .positions[rendered_body.index]).norm() > TRAJECTORY_MAX_SEGMENT_LENGTH)) {
-            let mut trajectory_segments_new: VecDeque<TrajectorySegment>
-                = (*trajectory_segments).clone();
-            trajectory_segments_new.push_back(
-                TrajectorySegment::new(props.rendered_state.rendered_bodies.iter()
-                                           .map(|rendered_body| rendered_body.body.position).collect(),
-                                       props.rendered_state.duration_elapsed_total,
-                ));
-
-            while let Some(last) = trajectory_segments_new.front() {
-                if props.rendered_state.duration_elapsed_total - last.recorded_after
-                    > settings.trajectory_duration {
-                    trajectory_segments_new.pop_front();
-                } else {
-                    break;
-                }
-            }
+        } else if trajectories_changed || drawn_changed {
+            *drawn.borrow_mut() = Some(drawn_new);
 
             context.clear().unwrap();
 
-            // Reversing for a more intuitive layer order.
-            for (body_index, rendered_body)
-            in props.rendered_state.rendered_bodies.iter().enumerate().rev() {
-                let starting_position = trajectory_segments_new.front().unwrap().positions[body_index];
-                context.set_stroke_style(&rendered_body.color.as_str().into());
-                context.begin_path();
-                context.move_to(starting_position.x, -starting_position.y);
-                for position in trajectory_segments_new.iter()
-                    .map(|trajectory_segment| trajectory_segment.positions[body_index]).skip(1) {
-                    context.line_to(position.x, -position.y);
+            // Reversing for a more intuitive layer order, with the selected trajectory on top.
+            let mut rendered_bodies = props.rendered_state.rendered_bodies.iter().enumerate().rev()
+                .collect::<Vec<_>>();
+            rendered_bodies.sort_by_key(|(_, rendered_body)| Some(rendered_body.label.id) == props.selected_body);
+            let reference = settings.trail_style.reference
+                .and_then(|reference| props.rendered_state.rendered_bodies.iter()
+                    .position(|rendered_body| rendered_body.label.id == reference))
+                .and_then(|reference_index| trajectories.get(reference_index));
+
+            context.save();
+            context.set_line_cap("round");
+            context.set_line_join("round");
+            for (body_index, rendered_body) in rendered_bodies {
+                let Some(trajectory) = trajectories.get(body_index) else {
+                    continue;
+                };
+                let line_width = if Some(rendered_body.label.id) == props.selected_body {
+                    TRAJECTORY_SELECTED_LINE_WIDTH
+                } else {
+                    TRAJECTORY_LINE_WIDTH
+                };
+
+                if settings.trail_style.is_uniform() {
+                    context.set_line_width(line_width);
+                    context.set_stroke_style(&rendered_body.color.as_str().into());
+                    trajectory.stroke(&context);
+                } else {
+                    draw_styled_trajectory(&context, trajectory, &rendered_body.color, line_width, &settings, reference,
+                        props.rendered_state.duration_elapsed_total);
                 }
-                context.stroke();
             }
-
-            trajectory_segments.set(trajectory_segments_new);
+            context.restore();
         }
     }
 
@@ -137,3 +170,91 @@ pub fn trajectory_canvas(props: &TrajectoryCanvasProps) -> Html {
         <canvas ref={canvas_ref} class="absolute"/>
     }
 }
+
+/// Draws the trajectory line by line, each in its own colour, opacity and width given by the trail style, stroking
+/// the lines of the same style at once.
+fn draw_styled_trajectory(
+    context: &CanvasRenderingContext2d,
+    trajectory: &Trajectory,
+    color: &str,
+    line_width: f64,
+    settings: &Settings,
+    reference: Option<&Trajectory>,
+    duration_elapsed_total: Duration,
+) {
+    let trail_style = settings.trail_style;
+    let points = trajectory.points();
+    let speeds = points.iter().map(|point| point.speed).collect::<Vec<_>>();
+    let values = match (trail_style.coloring, reference) {
+        (TrailColoring::Speed, _) => Some(speeds.clone()),
+        (TrailColoring::Distance, Some(reference)) => Some(points.iter()
+            .map(|point| reference.position_at(point.recorded_after)
+                .map_or(0f64, |reference_position| (point.position - reference_position).norm()))
+            .collect()),
+        _ => None,
+    };
+    let values_range = values.as_deref().map(range);
+    let speeds_range = range(&speeds);
+
+    // the lines by the levels of their colour value, if mapped, opacity and width
+    let mut lines_by_style = BTreeMap::<(Option<u32>, u32, u32), Vec<(&TrajectoryPoint, &TrajectoryPoint)>>::new();
+    for (index, (point_previous, point)) in points.iter().zip(points.iter().skip(1)).enumerate() {
+        let age = (duration_elapsed_total.saturating_sub(point.recorded_after).as_secs_f64()
+            / settings.trajectory_duration.as_secs_f64()).clamp(0f64, 1f64);
+
+        let color_value = match (&values, values_range) {
+            (Some(values), Some(values_range)) => Some(normalized(values[index + 1], values_range)),
+            _ => None,
+        };
+        let width_variation = match trail_style.width {
+            TrailWidth::Constant => 0f64,
+            TrailWidth::Age => 1f64 - age,
+            TrailWidth::Speed => normalized(speeds[index + 1], speeds_range),
+        };
+        let opacity = if trail_style.faded { 1f64 - age } else { 1f64 };
+
+        lines_by_style.entry((color_value.map(level), level(opacity), level(width_variation)))
+            .or_default()
+            .push((point_previous, point));
+    }
+
+    for ((color_level, opacity_level, width_level), lines) in lines_by_style {
+        let color = color_level.map_or_else(|| color.to_string(), |color_level| color_map(unlevel(color_level)));
+        context.set_global_alpha(unlevel(opacity_level));
+        context.set_line_width(line_width * (1f64 + TRAJECTORY_WIDTH_VARIATION * unlevel(width_level)));
+        context.set_stroke_style(&color.as_str().into());
+        context.begin_path();
+        for (point_previous, point) in lines {
+            context.move_to(point_previous.position.x, -point_previous.position.y);
+            context.line_to(point.position.x, -point.position.y);
+        }
+        context.stroke();
+    }
+}
+
+/// Rounds the value from 0 to 1 to one of the levels of the trail styles.
+fn level(value: f64) -> u32 {
+    (value.clamp(0f64, 1f64) * TRAIL_STYLE_LEVELS).round() as u32
+}
+
+fn unlevel(level: u32) -> f64 {
+    level as f64 / TRAIL_STYLE_LEVELS
+}
+
+fn range(values: &[f64]) -> (f64, f64) {
+    values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| (min.min(value), max.max(value)))
+}
+
+/// Maps the value to 0 at the minimum and 1 at the maximum, or 0.5 if they are equal.
+fn normalized(value: f64, (min, max): (f64, f64)) -> f64 {
+    if max > min {
+        (value - min) / (max - min)
+    } else {
+        0.5f64
+    }
+}
+
+/// Maps the value from 0 to 1 to the colour from blue to red.
+fn color_map(value: f64) -> String {
+    format!("hsl({:.0}, 90%, 60%)", 240f64 * (1f64 - value))
+}
@@ -3,22 +3,29 @@ use web_sys::{Element, HtmlInputElement, InputEvent};
 use web_sys::wasm_bindgen::JsCast;
 use yew::{Callback, Classes, classes, function_component, html, Html, Properties, use_effect_with, use_node_ref, use_state};
 
+use crate::models::validator::{ValidationError, Validator};
+
 #[derive(PartialEq, Properties)]
 pub struct ValidatedInputProps {
     pub(crate) id: String,
     #[prop_or_default]
     pub(crate) class: Classes,
     pub(crate) value: String,
-    pub(crate) on_input: Callback<String>,
+    /// Receives the value of the text entered once the validator accepts it.
+    pub(crate) on_input: Callback<f64>,
+    #[prop_or_default]
+    pub(crate) validator: Validator,
 }
 
 #[function_component(ValidatedInput)]
 pub fn validated_input(props: &ValidatedInputProps) -> Html {
     let value = use_state(|| props.value.clone());
+    let error = use_state(|| None::<ValidationError>);
     let input_ref = use_node_ref();
 
     {
         let value = value.clone();
+        let error = error.clone();
         let input_ref = input_ref.clone();
 
         use_effect_with(
@@ -27,36 +34,64 @@ pub fn validated_input(props: &ValidatedInputProps) -> Html {
                 if let (Some(input), Some(active_element)) = (input_ref.cast::<Element>(), document().active_element()) {
                     let input: Element = input;
 
-                    if active_element == input {
+                    // The invalid text is kept until it is corrected.
+                    if active_element == input || error.is_some() {
                         return;
                     }
 
                     value.set(value_new.clone());
+                    error.set(None);
                 }
             },
         );
     }
     
     let on_blur = {
-        let value_new = props.value.clone();
+        let value_old = props.value.clone();
+        let validator = props.validator.clone();
+        let on_input = props.on_input.clone();
         let value = value.clone();
-        props.on_input.reform(move |e: html::onblur::Event| {
-            value.set(value_new.clone());
-            e.target().unwrap().unchecked_into::<HtmlInputElement>().value()
+        let error = error.clone();
+        Callback::from(move |e: html::onblur::Event| {
+            let text = e.target().unwrap().unchecked_into::<HtmlInputElement>().value();
+            // Evaluating the unchanged text could differ in the last digits.
+            if text == value_old {
+                error.set(None);
+                return;
+            }
+
+            match validator.validate(&text) {
+                Ok(value_new) => {
+                    value.set(value_old.clone());
+                    error.set(None);
+                    on_input.emit(value_new);
+                }
+                Err(error_new) => error.set(Some(error_new)),
+            }
         })
     };
 
     html! {
-        <input
-            id={props.id.clone()}
-            class={classes!("bg-neutral-800", "text-right", "font-mono", "text-lg", "py-1", "px-3", "border", "border-neutral-500", "rounded", props.class.clone())}
-            type="text"
-            value={(*value).clone()}
-            onblur={on_blur}
-            oninput={Callback::from(move |e: InputEvent| {
-                value.set(e.target().unwrap().unchecked_into::<HtmlInputElement>().value());
-            })}
-            ref={input_ref}
-        />
+        <span class="inline-flex flex-col">
+            <input
+                id={props.id.clone()}
+                class={classes!(
+                    "bg-neutral-800", "text-right", "font-mono", "text-lg", "py-1", "px-3", "border", "rounded",
+                    if error.is_some() { "border-red-500" } else { "border-neutral-500" },
+                    props.class.clone(),
+                )}
+                type="text"
+                value={(*value).clone()}
+                title={error.as_ref().map(ToString::to_string)}
+                onblur={on_blur}
+                oninput={Callback::from(move |e: InputEvent| {
+                    value.set(e.target().unwrap().unchecked_into::<HtmlInputElement>().value());
+                })}
+                ref={input_ref}
+            />
+            if let Some(error) = &*error {
+                <span class="text-red-400 text-sm font-sans">{error.to_string()}</span>
+            }
+        </span>
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,114 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use web_sys::HtmlCanvasElement;
+use web_time::Duration;
+use yew::{function_component, html, use_context, use_effect_with, use_node_ref, use_state, Html, Properties};
+use yew_hooks::use_window_size;
+
+use crate::models::rendered_simulation_state::RenderedSimulationState;
+use crate::models::settings::Settings;
+use crate::models::trajectory_segment::TrajectorySegment;
+use crate::utils::webgl_renderer::WebGlRenderer;
+
+#[derive(Properties, PartialEq)]
+pub struct WebGlCanvasProps {
+    pub(crate) rendered_state: RenderedSimulationState,
+    /// The trajectory segments between the previous rendered state and the current one.
+    pub(crate) segments_intermediate: Vec<TrajectorySegment>,
+    pub(crate) rendered_bodies_edited_this_pause: bool,
+    pub(crate) simulation_paused: bool,
+    /// Changes when the agent's state is replaced (e.g. by a reset), which starts the trails over.
+    pub(crate) simulation_revision: u64,
+}
+
+/// Draws the bodies and their trajectories using WebGL 2, an alternative to the `TrajectoryCanvas` and the
+/// `BodyCanvas` suitable for many bodies and long trajectories.
+#[function_component(WebGlCanvas)]
+pub fn webgl_canvas(props: &WebGlCanvasProps) -> Html {
+    let canvas_ref = use_node_ref();
+    let canvas = canvas_ref.cast::<HtmlCanvasElement>();
+    let renderer = use_state(|| None::<Rc<RefCell<WebGlRenderer>>>);
+    let settings = use_context::<Settings>().unwrap();
+    let window_size = use_window_size();
+
+    {
+        let renderer = renderer.clone();
+        use_effect_with(
+            canvas.clone(),
+            move |canvas| {
+                if let Some(canvas) = canvas {
+                    match WebGlRenderer::new(canvas) {
+                        Ok(renderer_new) => renderer.set(Some(Rc::new(RefCell::new(renderer_new)))),
+                        Err(error) => log::error!("failed to initialize the WebGL renderer: {error}"),
+                    }
+                }
+            },
+        );
+    }
+
+    {
+        let renderer = renderer.clone();
+        use_effect_with(
+            props.rendered_bodies_edited_this_pause,
+            move |&rendered_bodies_edited_this_pause| {
+                if let Some(renderer) = (*renderer).as_ref() {
+                    if rendered_bodies_edited_this_pause {
+                        renderer.borrow_mut().clear_trails();
+                    }
+                }
+            },
+        );
+    }
+
+    {
+        let renderer = renderer.clone();
+        use_effect_with(
+            props.simulation_revision,
+            move |_| {
+                if let Some(renderer) = (*renderer).as_ref() {
+                    renderer.borrow_mut().clear_trails();
+                }
+            },
+        );
+    }
+
+    if let (Some(renderer), Some(canvas)) = ((*renderer).as_ref(), canvas) {
+        let width = window_size.0 as u32;
+        let height = (window_size.1 as u32).saturating_sub(150);
+        if canvas.width() != width || canvas.height() != height {
+            canvas.set_width(width);
+            canvas.set_height(height);
+        }
+
+        let mut renderer = renderer.borrow_mut();
+        let trajectories_shown = settings.trajectory_duration > Duration::ZERO;
+
+        if trajectories_shown && !props.simulation_paused {
+            // Those already recorded are skipped, e.g. when rendering again without a new state.
+            for segment in props.segments_intermediate.iter() {
+                if segment.recorded_after > renderer.trail_recorded_after_last() {
+                    renderer.record_trails(&segment.positions, segment.recorded_after);
+                }
+            }
+            renderer.record_trails(
+                &props.rendered_state.rendered_bodies.iter()
+                    .map(|rendered_body| rendered_body.body.position)
+                    .collect::<Vec<_>>(),
+                props.rendered_state.duration_elapsed_total,
+            );
+        }
+
+        renderer.draw(
+            &props.rendered_state.rendered_bodies,
+            &props.rendered_state.rendered_bodies.iter()
+                .map(|rendered_body| settings.body_circle_radius(&rendered_body.body))
+                .collect::<Vec<_>>(),
+            trajectories_shown.then(|| props.rendered_state.duration_elapsed_total.saturating_sub(settings.trajectory_duration)),
+        );
+    }
+
+    html! {
+        <canvas ref={canvas_ref} class="absolute"/>
+    }
+}
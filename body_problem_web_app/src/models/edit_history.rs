@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use body_problem::labeled::BodyId;
+
+use crate::models::rendered_simulation_state::RenderedSimulationState;
+
+const EDIT_HISTORY_LENGTH_MAX: usize = 100;
+
+/// Identifies edits that are merged into a single history entry when repeated, such as the continuous
+/// changes of a colour picker being dragged.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum EditCoalescing {
+    Color { id: BodyId },
+}
+
+/// The states before the edits, shared between the copies of the history made on every edit rather than copied.
+#[derive(Clone, PartialEq, Default)]
+pub(crate) struct EditHistory {
+    undo_states: VecDeque<Rc<RenderedSimulationState>>,
+    redo_states: Vec<Rc<RenderedSimulationState>>,
+    last_coalescing: Option<EditCoalescing>,
+}
+
+impl EditHistory {
+    /// Records the state before an edit is made, discarding the states that could be redone.
+    pub fn record(&mut self, state_before: RenderedSimulationState, coalescing: Option<EditCoalescing>) {
+        self.redo_states.clear();
+
+        if coalescing.is_some() && coalescing == self.last_coalescing {
+            return;
+        }
+        self.last_coalescing = coalescing;
+
+        self.undo_states.push_back(Rc::new(state_before));
+        if self.undo_states.len() > EDIT_HISTORY_LENGTH_MAX {
+            self.undo_states.pop_front();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_states.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_states.is_empty()
+    }
+
+    /// Returns the state to go back to, given the current one.
+    pub fn undo(&mut self, state_current: RenderedSimulationState) -> Option<RenderedSimulationState> {
+        let state = self.undo_states.pop_back()?;
+        self.redo_states.push(Rc::new(state_current));
+        self.last_coalescing = None;
+        Some(Rc::unwrap_or_clone(state))
+    }
+
+    pub fn redo(&mut self, state_current: RenderedSimulationState) -> Option<RenderedSimulationState> {
+        let state = self.redo_states.pop()?;
+        self.undo_states.push_back(Rc::new(state_current));
+        self.last_coalescing = None;
+        Some(Rc::unwrap_or_clone(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use body_problem::Body;
+    use body_problem::labeled::BodyLabel;
+    use nalgebra::Vector2;
+
+    use crate::models::rendered_body::RenderedBody;
+
+    use super::*;
+
+    /// A state told apart by its time, with a body of the colour.
+    fn state(seconds: u64, color: &str) -> RenderedSimulationState {
+        let label = BodyLabel::new(BodyId(1), "body".to_string(), None);
+        let body = Body::new(1f64, Vector2::zeros(), Vector2::zeros());
+        RenderedSimulationState::new(vec![RenderedBody::new(0, label, body, color.to_string())], Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn edits_are_undone_and_redone_in_order() {
+        let mut history = EditHistory::default();
+        assert!(!history.can_undo() && !history.can_redo());
+        history.record(state(0, "red"), None);
+        history.record(state(1, "red"), None);
+
+        assert!(history.undo(state(2, "red")) == Some(state(1, "red")));
+        assert!(history.undo(state(1, "red")) == Some(state(0, "red")));
+        assert!(history.undo(state(0, "red")).is_none());
+        assert!(history.can_redo());
+        assert!(history.redo(state(0, "red")) == Some(state(1, "red")));
+        assert!(history.redo(state(1, "red")) == Some(state(2, "red")));
+        assert!(history.redo(state(2, "red")).is_none());
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn edit_discards_the_states_to_redo() {
+        let mut history = EditHistory::default();
+        history.record(state(0, "red"), None);
+        assert!(history.undo(state(1, "red")).is_some());
+
+        history.record(state(0, "red"), None);
+
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn oldest_states_are_dropped_beyond_the_most_kept() {
+        let mut history = EditHistory::default();
+        for seconds in 0..EDIT_HISTORY_LENGTH_MAX as u64 + 10 {
+            history.record(state(seconds, "red"), None);
+        }
+
+        let mut undone = Vec::new();
+        while let Some(state) = history.undo(state(0, "red")) {
+            undone.push(state.duration_elapsed_total.as_secs());
+        }
+        assert_eq!(undone, (10..EDIT_HISTORY_LENGTH_MAX as u64 + 10).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn repeated_colour_edits_of_a_body_are_coalesced() {
+        let mut history = EditHistory::default();
+        let color_of = |id: u64| Some(EditCoalescing::Color { id: BodyId(id) });
+        history.record(state(0, "red"), color_of(1));
+        history.record(state(0, "orange"), color_of(1));
+        history.record(state(0, "yellow"), color_of(1));
+        // another body, then the first one again
+        history.record(state(0, "green"), color_of(2));
+        history.record(state(0, "blue"), color_of(1));
+        history.record(state(0, "violet"), None);
+        history.record(state(0, "black"), None);
+
+        let mut undone = Vec::new();
+        while let Some(state) = history.undo(state(0, "white")) {
+            undone.push(state.rendered_bodies[0].color.clone());
+        }
+        assert_eq!(undone, ["black", "violet", "blue", "green", "red"]);
+    }
+
+    #[test]
+    fn undoing_ends_the_coalescing() {
+        let mut history = EditHistory::default();
+        let color = Some(EditCoalescing::Color { id: BodyId(1) });
+        history.record(state(0, "red"), None);
+        history.record(state(0, "orange"), color);
+        assert!(history.undo(state(0, "yellow")).is_some());
+
+        history.record(state(0, "orange"), color);
+
+        assert!(history.undo(state(0, "green")) == Some(state(0, "orange")));
+        assert!(history.undo(state(0, "orange")) == Some(state(0, "red")));
+    }
+
+    #[test]
+    fn copies_share_the_states() {
+        let mut history = EditHistory::default();
+        history.record(state(0, "red"), None);
+
+        let copy = history.clone();
+
+        assert!(Rc::ptr_eq(&history.undo_states[0], &copy.undo_states[0]));
+    }
+}
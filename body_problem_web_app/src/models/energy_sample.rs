@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use body_problem::{angular_momentum, kinetic_energy, potential_energy, Body};
+
+#[derive(Clone, PartialEq)]
+pub(crate) struct EnergySample {
+    pub(crate) recorded_after: Duration,
+    pub(crate) kinetic_energy: f64,
+    pub(crate) potential_energy: f64,
+    pub(crate) angular_momentum: f64,
+}
+
+impl EnergySample {
+    pub fn new(bodies: &[Body], recorded_after: Duration) -> Self {
+        Self {
+            recorded_after,
+            kinetic_energy: kinetic_energy(bodies),
+            potential_energy: potential_energy(bodies),
+            angular_momentum: angular_momentum(bodies),
+        }
+    }
+
+    pub fn total_energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+}
@@ -0,0 +1,206 @@
+use std::ops::Deref;
+use std::rc::Rc;
+
+use body_problem::Body;
+
+use crate::models::unit_system::{Quantity, UnitSystem};
+use crate::utils::expression::{evaluate, Dimension, ExpressionError, ExpressionNames, Value};
+
+/// What the expressions typed into an input can refer to besides the built-in constants and functions: the
+/// gravitational constant `G`, the masses `M0`, positions `x0`, `y0` and velocities `vx0`, `vy0` of the bodies numbered
+/// from 0, and, for the body the value is entered for, `circular(0)`, the velocity of a circular orbit around body 0,
+/// and `distance(0)`. All of them are in the units of the unit system.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub(crate) struct ExpressionScope {
+    pub(crate) unit_system: UnitSystem,
+    /// The quantity the value is of, converted from the unit system to the SI units. The unit symbols like `AU` can
+    /// only be used with it, the result being either of the quantity or without a unit.
+    pub(crate) quantity: Option<Quantity>,
+    pub(crate) bodies: SharedBodies,
+    pub(crate) body_index: Option<usize>,
+}
+
+/// The bodies shared by the scopes of all the inputs of a table, compared by identity, as comparing them by value for
+/// each input would take a time quadratic in their number.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct SharedBodies(Rc<Vec<Body>>);
+
+impl PartialEq for SharedBodies {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<Vec<Body>> for SharedBodies {
+    fn from(bodies: Vec<Body>) -> Self {
+        Self(Rc::new(bodies))
+    }
+}
+
+impl Deref for SharedBodies {
+    type Target = [Body];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl ExpressionScope {
+    pub fn new(unit_system: UnitSystem, quantity: Option<Quantity>, bodies: SharedBodies, body_index: Option<usize>) -> Self {
+        Self { unit_system, quantity, bodies, body_index }
+    }
+
+    /// Evaluates the expression, returning the value in the SI units.
+    pub fn evaluate(&self, text: &str) -> Result<f64, ExpressionError> {
+        let value = evaluate(text, self)?;
+        match self.quantity {
+            Some(quantity) if !value.dimension.is_none() && value.dimension != quantity.dimension() => {
+                Err(ExpressionError::WrongUnit)
+            }
+            Some(quantity) => Ok(value.number * self.unit_system.unit(quantity).factor),
+            None => Ok(value.number),
+        }
+    }
+
+    /// Returns the value in the SI units of the quantity in the unit of this system.
+    fn value(&self, value_si: f64, quantity: Quantity) -> Value {
+        Value::new(value_si / self.unit_system.unit(quantity).factor, quantity.dimension())
+    }
+
+    fn body(&self, index: f64) -> Result<&Body, ExpressionError> {
+        let index_rounded = index.max(0f64).round() as usize;
+        if index.fract() != 0f64 {
+            return Err(ExpressionError::NoSuchBody(index_rounded));
+        }
+        self.bodies.get(index_rounded).ok_or(ExpressionError::NoSuchBody(index_rounded))
+    }
+
+    /// Returns the distance in the SI units between the body the value is entered for and the other one.
+    fn distance(&self, function: &str, arguments: &[Value]) -> Result<f64, ExpressionError> {
+        let [index] = arguments else {
+            return Err(ExpressionError::ArgumentCount { function: function.to_string(), expected: 1 });
+        };
+        let body = self.body_index
+            .and_then(|index| self.bodies.get(index))
+            .ok_or(ExpressionError::NoCurrentBody(function.to_string()))?;
+        Ok((self.body(index.number)?.position - body.position).norm())
+    }
+}
+
+impl ExpressionNames for ExpressionScope {
+    fn variable(&self, name: &str) -> Option<Result<Value, ExpressionError>> {
+        if name == "G" {
+            // in m³/(kg s²)
+            return Some(Ok(Value::new(self.unit_system.gravitational_constant(), Dimension([3f64, -1f64, -2f64]))));
+        }
+
+        let digits_start = name.find(|character: char| character.is_ascii_digit());
+        if let Some(body) = digits_start.and_then(|start| self.bodies.get(name[start..].parse::<usize>().ok()?)) {
+            let value = match &name[..digits_start.unwrap()] {
+                "M" => self.value(body.mass, Quantity::Mass),
+                "x" => self.value(body.position.x, Quantity::Length),
+                "y" => self.value(body.position.y, Quantity::Length),
+                "vx" => self.value(body.velocity.x, Quantity::Velocity),
+                "vy" => self.value(body.velocity.y, Quantity::Velocity),
+                _ => return None,
+            };
+            return Some(Ok(value));
+        }
+
+        self.quantity
+            .and_then(|_| self.unit_system.unit_value(name))
+            .map(|(value, quantity)| Ok(Value::new(value, quantity.dimension())))
+    }
+
+    fn function(&self, name: &str, arguments: &[Value]) -> Option<Result<Value, ExpressionError>> {
+        match name {
+            "circular" => Some(self.distance(name, arguments).and_then(|distance| {
+                let mass = self.body(arguments[0].number)?.mass;
+                Ok(self.value((body_problem::GRAVITATIONAL_CONSTANT * mass / distance).sqrt(), Quantity::Velocity))
+            })),
+            "distance" => Some(self.distance(name, arguments)
+                .map(|distance| self.value(distance, Quantity::Length))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+
+    const ASTRONOMICAL_UNIT: f64 = 1.495978707e11;
+    const SOLAR_MASS: f64 = 1.98847e30;
+    const YEAR: f64 = 365.25 * 86_400f64;
+
+    fn evaluate_in(unit_system: UnitSystem, quantity: Quantity, text: &str) -> Result<f64, ExpressionError> {
+        ExpressionScope::new(unit_system, Some(quantity), SharedBodies::default(), None).evaluate(text)
+    }
+
+    fn assert_close(value: Result<f64, ExpressionError>, expected: f64) {
+        let value = value.unwrap();
+        assert!((value - expected).abs() <= 1e-12 * expected.abs(), "{value} instead of {expected}");
+    }
+
+    #[test]
+    fn units_of_the_quantity_are_converted() {
+        assert_close(evaluate_in(UnitSystem::Si, Quantity::Length, "2 km"), 2e3);
+        assert_close(evaluate_in(UnitSystem::Astronomical, Quantity::Mass, "Msun"), SOLAR_MASS);
+        assert_close(evaluate_in(UnitSystem::Natural, Quantity::Mass, "3 M"), 3f64 / body_problem::GRAVITATIONAL_CONSTANT);
+    }
+
+    #[test]
+    fn velocities_are_quotients_of_units() {
+        assert_close(evaluate_in(UnitSystem::Si, Quantity::Velocity, "30 km/s"), 3e4);
+        assert_close(evaluate_in(UnitSystem::Si, Quantity::Velocity, "30 m/s"), 30f64);
+        assert_close(evaluate_in(UnitSystem::Si, Quantity::Velocity, "2 AU/yr"), 2f64 * ASTRONOMICAL_UNIT / YEAR);
+        assert_close(evaluate_in(UnitSystem::Astronomical, Quantity::Velocity, "2 AU/yr"), 2f64 * ASTRONOMICAL_UNIT / YEAR);
+        assert_close(evaluate_in(UnitSystem::Astronomical, Quantity::Velocity, "1 km/s"), 1e3);
+    }
+
+    #[test]
+    fn formatted_values_are_read_back() {
+        for unit_system in UnitSystem::ALL {
+            for (quantity, value) in [(Quantity::Length, 1.5e11), (Quantity::Mass, 2e30), (Quantity::Velocity, -2.9e4)] {
+                let text = unit_system.format_input(value, quantity);
+                assert_close(evaluate_in(unit_system, quantity, &text), value);
+            }
+        }
+    }
+
+    #[test]
+    fn units_can_be_combined_with_the_constants() {
+        let velocity_circular = (body_problem::GRAVITATIONAL_CONSTANT * SOLAR_MASS / ASTRONOMICAL_UNIT).sqrt();
+        for unit_system in UnitSystem::ALL {
+            assert_close(evaluate_in(unit_system, Quantity::Velocity, "sqrt(G*Msun/AU)"), velocity_circular);
+        }
+    }
+
+    #[test]
+    fn unitless_numbers_are_in_the_unit_of_the_field() {
+        let bodies = vec![Body::new(1f64, Vector2::new(ASTRONOMICAL_UNIT, 0f64), Vector2::zeros())];
+        let scope = ExpressionScope::new(UnitSystem::Astronomical, Some(Quantity::Length), bodies.into(), None);
+
+        assert_close(scope.evaluate("x0 + 2"), 3f64 * ASTRONOMICAL_UNIT);
+        assert_close(scope.evaluate("3"), 3f64 * ASTRONOMICAL_UNIT);
+    }
+
+    #[test]
+    fn shared_bodies_are_compared_by_identity() {
+        let bodies = SharedBodies::from(vec![Body::new(1f64, Vector2::zeros(), Vector2::zeros())]);
+
+        assert_eq!(bodies.clone(), bodies);
+        assert_ne!(SharedBodies::from(bodies.to_vec()), bodies);
+    }
+
+    #[test]
+    fn units_of_other_quantities_are_rejected() {
+        assert_eq!(evaluate_in(UnitSystem::Si, Quantity::Mass, "5 AU"), Err(ExpressionError::WrongUnit));
+        assert_eq!(evaluate_in(UnitSystem::Si, Quantity::Velocity, "3 d"), Err(ExpressionError::WrongUnit));
+        assert_eq!(evaluate_in(UnitSystem::Si, Quantity::Velocity, "30 km"), Err(ExpressionError::WrongUnit));
+        assert_eq!(evaluate_in(UnitSystem::Natural, Quantity::Length, "M"), Err(ExpressionError::WrongUnit));
+        assert_eq!(evaluate_in(UnitSystem::Si, Quantity::Length, "1 AU + 1 s"), Err(ExpressionError::UnitMismatch));
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+pub(crate) const FIELD_LAYER_DEFAULT: FieldLayer = FieldLayer::new(FieldQuantity::None, false);
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum FieldQuantity {
+    None,
+    Potential,
+    FieldMagnitude,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FieldLayer {
+    pub(crate) quantity: FieldQuantity,
+    pub(crate) contours_shown: bool,
+}
+
+impl FieldLayer {
+    pub const fn new(quantity: FieldQuantity, contours_shown: bool) -> Self {
+        Self { quantity, contours_shown }
+    }
+}
+
+impl Default for FieldLayer {
+    fn default() -> Self {
+        FIELD_LAYER_DEFAULT
+    }
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use body_problem::integrator::Integrator;
+
+use crate::agents::simulation_reactor::{STEP_DEFAULT, TARGET_FPS_DEFAULT};
+
+pub(crate) const INTEGRATION_DEFAULT: Integration = Integration::new(Integrator::SemiImplicitEuler, STEP_DEFAULT, TARGET_FPS_DEFAULT);
+
+/// How the simulation is advanced, i.e. its accuracy and smoothness.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Integration {
+    pub(crate) integrator: Integrator,
+    /// The simulated time of a single step.
+    pub(crate) step: f64,
+    /// How many times per second the simulation sends its state.
+    pub(crate) target_fps: f64,
+}
+
+impl Integration {
+    pub const fn new(integrator: Integrator, step: f64, target_fps: f64) -> Self {
+        Self { integrator, step, target_fps }
+    }
+
+    /// Returns the number of steps a frame lasts at the real speed.
+    pub fn steps_per_frame(&self) -> u64 {
+        ((1f64 / self.target_fps) / self.step).round().max(1f64) as u64
+    }
+}
+
+impl Default for Integration {
+    fn default() -> Self {
+        INTEGRATION_DEFAULT
+    }
+}
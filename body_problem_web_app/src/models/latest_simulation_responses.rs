@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::agents::simulation_reactor::{FrameStatistics, RecordingStatus, SimulationReactorOutput, SimulationResponse};
+
+/// The latest of the responses of the simulation agent, taken over as they arrive rather than searched for among all
+/// of them on every render.
+#[derive(Default)]
+pub(crate) struct LatestSimulationResponses {
+    /// The number of the responses taken over.
+    count: usize,
+    /// The latest response carrying the states or telling the simulation paused.
+    state: Option<Rc<SimulationResponse>>,
+    duration_recorded: Duration,
+    recording_status: RecordingStatus,
+    /// The statistics of the latest frames, at most as many as kept, the oldest first.
+    frame_statistics: VecDeque<FrameStatistics>,
+}
+
+impl LatestSimulationResponses {
+    /// Takes over the responses following those already taken over, keeping the statistics of the frames up to the
+    /// number.
+    pub fn update(&mut self, responses: &[Rc<SimulationResponse>], frame_statistics_max: usize) {
+        for response in responses.iter().skip(self.count) {
+            match response.as_ref() {
+                SimulationResponse::State(output) => {
+                    self.state = Some(response.clone());
+                    self.duration_recorded = output.duration_recorded;
+                    if let Some(statistics) = &output.statistics {
+                        self.frame_statistics.push_back(statistics.clone());
+                    }
+                }
+                SimulationResponse::Paused { .. } => self.state = Some(response.clone()),
+                SimulationResponse::RecordingStatus(status) => self.recording_status = *status,
+                _ => {}
+            }
+        }
+        while self.frame_statistics.len() > frame_statistics_max {
+            self.frame_statistics.pop_front();
+        }
+        self.count = responses.len();
+    }
+
+    /// Returns the latest states of the revision, unless the simulation was paused since.
+    pub fn output(&self, revision: u64) -> Option<&SimulationReactorOutput> {
+        // The agent sends the responses in the order of the revisions, so that the latest one is of the revision if
+        // any is.
+        match self.state.as_deref() {
+            Some(SimulationResponse::State(output)) if output.revision == revision => Some(output),
+            _ => None,
+        }
+    }
+
+    /// Returns how far the timeline has been simulated.
+    pub fn duration_recorded(&self) -> Duration {
+        self.duration_recorded
+    }
+
+    pub fn recording_status(&self) -> RecordingStatus {
+        self.recording_status
+    }
+
+    pub fn frame_statistics(&self) -> Vec<FrameStatistics> {
+        self.frame_statistics.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::agents::simulation_reactor::{RecordingChange, SimulationState};
+
+    use super::*;
+
+    fn paused(revision: u64) -> Rc<SimulationResponse> {
+        Rc::new(SimulationResponse::Paused { state: SimulationState::new(Vec::new(), Duration::ZERO), revision })
+    }
+
+    fn recording_status(change: RecordingChange) -> Rc<SimulationResponse> {
+        Rc::new(SimulationResponse::RecordingStatus(RecordingStatus { recording: false, recorded: true, change }))
+    }
+
+    #[test]
+    fn only_the_new_responses_are_taken_over() {
+        let mut latest = LatestSimulationResponses::default();
+        let mut responses = vec![recording_status(RecordingChange::Started), paused(1)];
+        latest.update(&responses, 10);
+        assert!(latest.recording_status().change == RecordingChange::Started);
+        assert!(latest.output(1).is_none());
+
+        responses.push(recording_status(RecordingChange::Full));
+        latest.update(&responses, 10);
+        assert!(latest.recording_status().change == RecordingChange::Full);
+
+        // Those taken over already are not taken over again.
+        responses[2] = recording_status(RecordingChange::Reset);
+        latest.update(&responses, 10);
+        assert!(latest.recording_status().change == RecordingChange::Full);
+        assert_eq!(latest.duration_recorded(), Duration::ZERO);
+        assert!(latest.frame_statistics().is_empty());
+    }
+}
@@ -1,3 +1,17 @@
+pub(crate) mod edit_history;
+pub(crate) mod energy_sample;
+pub(crate) mod expression_scope;
+pub(crate) mod field_layer;
+pub(crate) mod integration;
+pub(crate) mod latest_simulation_responses;
+pub(crate) mod preset;
 pub(crate) mod rendered_body;
+pub(crate) mod rendered_simulation_state;
+pub(crate) mod renderer;
+pub(crate) mod session;
 pub(crate) mod settings;
+pub(crate) mod trail_style;
 pub(crate) mod trajectory_segment;
+pub(crate) mod unit_system;
+pub(crate) mod validator;
+pub(crate) mod vector_overlays;
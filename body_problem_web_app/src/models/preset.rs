@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use nalgebra::Vector2;
+
+use body_problem::Body;
+use body_problem::galaxy::{DiscGalaxy, GalaxyCollision, Spin};
+use body_problem::labeled::{BodyId, BodyLabel};
+
+use crate::models::rendered_simulation_state::RenderedSimulationState;
+use crate::models::rendered_body::RenderedBody;
+
+const GALAXY_CORE_COLOR: &str = "#ffff3f";
+const GALAXY_PARTICLE_COLORS: [&str; 2] = ["#5a8cc8", "#e0703c"];
+// a parabolic encounter of two unequal galaxies, one disc rotating against the orbit
+const GALAXY_COLLISION: GalaxyCollision = GalaxyCollision::new(
+    [DiscGalaxy::new(1e17, 120f64, 150, Spin::Prograde), DiscGalaxy::new(5e16, 90f64, 100, Spin::Retrograde)],
+    250f64,
+    1f64,
+    800f64,
+);
+
+/// The bodies the simulation can be started over with.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum Preset {
+    #[default]
+    StarPlanetMoon,
+    GalaxyCollision,
+}
+
+impl Preset {
+    pub const ALL: [Self; 2] = [Self::StarPlanetMoon, Self::GalaxyCollision];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StarPlanetMoon => "star, planet and moon",
+            Self::GalaxyCollision => "galaxy collision",
+        }
+    }
+
+    pub fn rendered_state(&self) -> RenderedSimulationState {
+        let rendered_bodies = match self {
+            Self::StarPlanetMoon => vec![
+                RenderedBody::new(0, BodyLabel::new(BodyId(1), "star".to_string(), None), Body::new(1e17, Vector2::new(0f64, 0f64), Vector2::new(0f64, -1.52f64)), "#ffff3f".to_string()),
+                RenderedBody::new(1, BodyLabel::new(BodyId(2), "planet".to_string(), None), Body::new(1e15, Vector2::new(300f64, 0f64), Vector2::new(0f64, 149.76f64)), "#5a8cc8".to_string()),
+                RenderedBody::new(2, BodyLabel::new(BodyId(3), "moon".to_string(), None), Body::new(1e13, Vector2::new(320f64, 0f64), Vector2::new(0f64, 206.92f64)), "#bfbfbf".to_string()),
+            ],
+            Self::GalaxyCollision => {
+                // Each galaxy's bodies start with its core.
+                let core_indices = [0, 1 + GALAXY_COLLISION.galaxies[0].particle_count];
+                GALAXY_COLLISION.bodies().into_iter()
+                    .enumerate()
+                    .map(|(index, body)| {
+                        let galaxy = usize::from(index >= core_indices[1]);
+                        let (name, color) = if core_indices.contains(&index) {
+                            (format!("galaxy {} core", galaxy + 1), GALAXY_CORE_COLOR)
+                        } else {
+                            (format!("galaxy {} star {}", galaxy + 1, index - core_indices[galaxy]), GALAXY_PARTICLE_COLORS[galaxy])
+                        };
+                        RenderedBody::new(index, BodyLabel::new(BodyId(index as u64 + 1), name, None), body, color.to_string())
+                    })
+                    .collect()
+            }
+        };
+        RenderedSimulationState::new(rendered_bodies, Duration::ZERO)
+    }
+}
@@ -1,15 +1,20 @@
 use body_problem::Body;
+use body_problem::labeled::BodyLabel;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct RenderedBody {
     pub(crate) index: usize,
+    // missing in the sessions stored before the bodies were labeled
+    #[serde(default)]
+    pub(crate) label: BodyLabel,
     pub(crate) body: Body,
     pub(crate) potential_energy: f64,
     pub(crate) color: String,
 }
 
 impl RenderedBody {
-    pub const fn new(index: usize, body: Body, color: String) -> Self {
-        Self { index, body, potential_energy: 0f64, color }
+    pub const fn new(index: usize, label: BodyLabel, body: Body, color: String) -> Self {
+        Self { index, label, body, potential_energy: 0f64, color }
     }
 }
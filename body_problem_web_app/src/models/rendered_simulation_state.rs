@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use body_problem::Body;
+use body_problem::labeled::{BodyId, BodyLabel};
+
+use crate::agents::simulation_reactor::SimulationState;
+use crate::models::rendered_body::RenderedBody;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RenderedSimulationState {
+    pub(crate) rendered_bodies: Vec<RenderedBody>,
+    pub(crate) duration_elapsed_total: Duration,
+}
+
+impl RenderedSimulationState {
+    pub fn new(rendered_bodies: Vec<RenderedBody>, duration_elapsed_total: Duration) -> Self {
+        Self { rendered_bodies, duration_elapsed_total }
+    }
+
+    pub fn bodies(&self) -> Vec<Body> {
+        self.rendered_bodies.iter().map(|rendered_body| rendered_body.body.clone()).collect()
+    }
+
+    /// Renders the simulation state with the colors of the bodies of this state.
+    pub fn with_simulation_state(&self, state: &SimulationState) -> Self {
+        Self::new(
+            state.bodies.iter()
+                .enumerate()
+                .map(|(index, body)| {
+                    RenderedBody {
+                        index,
+                        label: self.rendered_bodies[index].label.clone(),
+                        body: body.clone(),
+                        potential_energy: state.bodies.iter()
+                            .enumerate()
+                            .filter(|(index2, _)| index != *index2)
+                            .map(|(_, body2)| body.potential_energy_to(body2))
+                            .sum(),
+                        color: self.rendered_bodies[index].color.clone(),
+                    }
+                }).collect(), state.duration_elapsed_total)
+    }
+
+    pub fn body_id_unused(&self) -> BodyId {
+        BodyId::unused(self.rendered_bodies.iter().map(|rendered_body| &rendered_body.label.id))
+    }
+
+    /// Labels the bodies of the sessions stored before the bodies were labeled.
+    pub fn label_unlabeled(&mut self) {
+        for index in 0..self.rendered_bodies.len() {
+            if self.rendered_bodies[index].label.id == BodyId::default() {
+                let id = self.body_id_unused();
+                self.rendered_bodies[index].label = BodyLabel::new(id, format!("body {}", id.0), None);
+            }
+        }
+    }
+}
+
+impl PartialEq for RenderedSimulationState {
+    fn eq(&self, other: &Self) -> bool {
+        self.rendered_bodies.iter().eq(other.rendered_bodies.iter())
+            && self.duration_elapsed_total == other.duration_elapsed_total
+    }
+}
+
+impl From<RenderedSimulationState> for SimulationState {
+    fn from(rendered_simulation_state: RenderedSimulationState) -> Self {
+        Self::new(rendered_simulation_state.rendered_bodies.iter().map(|b| b.body.clone()).collect(), rendered_simulation_state.duration_elapsed_total)
+    }
+}
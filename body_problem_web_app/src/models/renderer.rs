@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) enum Renderer {
+    #[default]
+    Canvas2d,
+    WebGl2,
+}
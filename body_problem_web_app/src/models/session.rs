@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::models::rendered_simulation_state::RenderedSimulationState;
+use crate::models::settings::Settings;
+
+const SESSION_LAST_STORAGE_KEY: &str = "session_last";
+const SESSIONS_SAVED_STORAGE_KEY: &str = "sessions_saved";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Session {
+    pub(crate) rendered_state: RenderedSimulationState,
+    pub(crate) settings: Settings,
+}
+
+impl Session {
+    pub fn new(rendered_state: RenderedSimulationState, settings: Settings) -> Self {
+        Self { rendered_state, settings }
+    }
+
+    /// Returns the session stored by the last call to `store_last`, if there is a valid one.
+    pub fn load_last() -> Option<Self> {
+        LocalStorage::get(SESSION_LAST_STORAGE_KEY).ok().map(Self::labeled)
+    }
+
+    pub fn store_last(&self) {
+        // The storage may be full or disabled, in which case there is nothing better to do.
+        let _ = LocalStorage::set(SESSION_LAST_STORAGE_KEY, self);
+    }
+
+    /// Returns the sessions saved by the user under their names.
+    pub fn load_saved() -> BTreeMap<String, Self> {
+        LocalStorage::get::<BTreeMap<String, Self>>(SESSIONS_SAVED_STORAGE_KEY).unwrap_or_default()
+            .into_iter()
+            .map(|(name, session)| (name, session.labeled()))
+            .collect()
+    }
+
+    pub fn store_saved(sessions: &BTreeMap<String, Self>) {
+        let _ = LocalStorage::set(SESSIONS_SAVED_STORAGE_KEY, sessions);
+    }
+
+    fn labeled(mut self) -> Self {
+        self.rendered_state.label_unlabeled();
+        self
+    }
+}
@@ -1,15 +1,122 @@
 use std::time::Duration;
 
-#[derive(Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use body_problem::Body;
+use body_problem::labeled::BodyId;
+
+use crate::models::field_layer::{FieldLayer, FIELD_LAYER_DEFAULT};
+use crate::models::integration::{Integration, INTEGRATION_DEFAULT};
+use crate::models::renderer::Renderer;
+use crate::models::trail_style::{TrailStyle, TRAIL_STYLE_DEFAULT};
+use crate::models::unit_system::UnitSystem;
+use crate::models::vector_overlays::{VectorOverlays, VECTOR_OVERLAYS_DEFAULT};
+
+/// The smallest radius a body scaled with its mass is drawn with, so that the massless test particles stay visible.
+const BODY_CIRCLE_RADIUS_MIN: f64 = 1f64;
+
+pub(crate) const SETTINGS_DEFAULT: Settings = Settings {
+    trajectory_duration: Duration::from_secs(5),
+    simulation_speed: 1f64,
+    forecast_duration: Duration::from_secs(5),
+    body_circle_radius: 0.0001f64,
+    scale_body_circles_with_mass: true,
+    vector_overlays: VECTOR_OVERLAYS_DEFAULT,
+    field_layer: FIELD_LAYER_DEFAULT,
+    renderer: Renderer::Canvas2d,
+    integration: INTEGRATION_DEFAULT,
+    performance_shown: false,
+    labels_shown: true,
+    unit_system: UnitSystem::Si,
+    conic_primary: None,
+    trail_style: TRAIL_STYLE_DEFAULT,
+};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Settings {
     pub(crate) trajectory_duration: Duration,
     pub(crate) simulation_speed: f64,
+    /// How far the paths of the bodies are predicted while the simulation is paused.
+    #[serde(default = "forecast_duration_default")]
+    pub(crate) forecast_duration: Duration,
     pub(crate) body_circle_radius: f64,
     pub(crate) scale_body_circles_with_mass: bool,
+    #[serde(default)]
+    pub(crate) vector_overlays: VectorOverlays,
+    #[serde(default)]
+    pub(crate) field_layer: FieldLayer,
+    #[serde(default)]
+    pub(crate) renderer: Renderer,
+    #[serde(default)]
+    pub(crate) integration: Integration,
+    /// Whether the overlay with the statistics of the simulation frames is shown.
+    #[serde(default)]
+    pub(crate) performance_shown: bool,
+    /// Whether the names of the bodies are shown next to them.
+    #[serde(default = "labels_shown_default")]
+    pub(crate) labels_shown: bool,
+    #[serde(default)]
+    pub(crate) unit_system: UnitSystem,
+    /// The body relative to which the Kepler orbits of the others are drawn, if any.
+    #[serde(default)]
+    pub(crate) conic_primary: Option<BodyId>,
+    #[serde(default)]
+    pub(crate) trail_style: TrailStyle,
 }
 
 impl Settings {
-    pub const fn new(trajectory_duration: Duration, simulation_speed: f64, body_circle_radius: f64, scale_body_circles_with_mass: bool) -> Self {
-        Self { trajectory_duration, simulation_speed, body_circle_radius, scale_body_circles_with_mass }
+    pub fn body_circle_radius(&self, body: &Body) -> f64 {
+        if self.scale_body_circles_with_mass {
+            (self.body_circle_radius * body.mass.cbrt()).max(BODY_CIRCLE_RADIUS_MIN)
+        } else {
+            self.body_circle_radius
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        SETTINGS_DEFAULT
+    }
+}
+
+// for the settings saved before the fields were added
+fn forecast_duration_default() -> Duration {
+    SETTINGS_DEFAULT.forecast_duration
+}
+
+fn labels_shown_default() -> bool {
+    SETTINGS_DEFAULT.labels_shown
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+
+    #[test]
+    fn massless_bodies_scaled_with_mass_stay_visible() {
+        let settings = Settings { scale_body_circles_with_mass: true, ..SETTINGS_DEFAULT };
+        let body = Body::new(0f64, Vector2::zeros(), Vector2::zeros());
+
+        assert_eq!(settings.body_circle_radius(&body), BODY_CIRCLE_RADIUS_MIN);
+    }
+
+    /// Returns the default settings read back without the field, as saved before it was added.
+    fn deserialized_without(field: &str) -> Settings {
+        let mut value = serde_json::to_value(SETTINGS_DEFAULT).unwrap();
+        value.as_object_mut().unwrap().remove(field);
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn missing_forecast_duration_is_the_default() {
+        assert_eq!(deserialized_without("forecast_duration").forecast_duration, SETTINGS_DEFAULT.forecast_duration);
+    }
+
+    #[test]
+    fn missing_labels_shown_is_the_default() {
+        assert!(deserialized_without("labels_shown").labels_shown);
     }
 }
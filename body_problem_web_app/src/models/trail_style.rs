@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use body_problem::labeled::BodyId;
+
+pub(crate) const TRAIL_STYLE_DEFAULT: TrailStyle = TrailStyle::new(TrailColoring::Body, None, false, TrailWidth::Constant);
+
+/// What the colour along a trail shows. The mapped quantities range from blue for the lowest value of the trail to
+/// red for the highest.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum TrailColoring {
+    /// The colour of the body.
+    Body,
+    Speed,
+    /// The distance to the reference body.
+    Distance,
+}
+
+impl TrailColoring {
+    pub const ALL: [Self; 3] = [Self::Body, Self::Speed, Self::Distance];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Body => "body",
+            Self::Speed => "speed",
+            Self::Distance => "distance",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum TrailWidth {
+    Constant,
+    /// Narrowing with the age.
+    Age,
+    /// Wider where the body is faster.
+    Speed,
+}
+
+impl TrailWidth {
+    pub const ALL: [Self; 3] = [Self::Constant, Self::Age, Self::Speed];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Constant => "constant",
+            Self::Age => "age",
+            Self::Speed => "speed",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TrailStyle {
+    pub(crate) coloring: TrailColoring,
+    /// The body the distance is measured to when colouring by the distance.
+    pub(crate) reference: Option<BodyId>,
+    /// Whether the trails fade out with the age over the trajectory duration.
+    pub(crate) faded: bool,
+    pub(crate) width: TrailWidth,
+}
+
+impl TrailStyle {
+    pub const fn new(coloring: TrailColoring, reference: Option<BodyId>, faded: bool, width: TrailWidth) -> Self {
+        Self { coloring, reference, faded, width }
+    }
+
+    /// Whether each trail is drawn in a single colour, opacity and width.
+    pub fn is_uniform(&self) -> bool {
+        self.coloring == TrailColoring::Body && !self.faded && self.width == TrailWidth::Constant
+    }
+}
+
+impl Default for TrailStyle {
+    fn default() -> Self {
+        TRAIL_STYLE_DEFAULT
+    }
+}
@@ -1,14 +1,22 @@
 use nalgebra::Vector2;
 use std::time::Duration;
 
+use body_problem::Body;
+
 #[derive(Clone, PartialEq)]
 pub(crate) struct TrajectorySegment {
     pub(crate) positions: Vec<Vector2<f64>>,
+    /// The speeds of the bodies at the positions, which the trails can be coloured by.
+    pub(crate) speeds: Vec<f64>,
     pub(crate) recorded_after: Duration,
 }
 
 impl TrajectorySegment {
-    pub fn new(positions: Vec<Vector2<f64>>, recorded_after: Duration) -> Self {
-        Self { positions, recorded_after }
+    pub fn new(bodies: &[Body], recorded_after: Duration) -> Self {
+        Self {
+            positions: bodies.iter().map(|body| body.position).collect(),
+            speeds: bodies.iter().map(|body| body.velocity.norm()).collect(),
+            recorded_after,
+        }
     }
 }
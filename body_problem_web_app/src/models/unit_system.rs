@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+
+use body_problem::GRAVITATIONAL_CONSTANT;
+
+use crate::utils::expression::Dimension;
+
+const ASTRONOMICAL_UNIT: f64 = 1.495978707e11;
+const SOLAR_MASS: f64 = 1.98847e30;
+const EARTH_MASS: f64 = 5.9722e24;
+const JUPITER_MASS: f64 = 1.89813e27;
+const LIGHT_YEAR: f64 = 9.4607304725808e15;
+const PARSEC: f64 = 3.085677581491367e16;
+const DAY: f64 = 86_400f64;
+// the Julian year
+const YEAR: f64 = 365.25 * DAY;
+// the mass for which the gravitational constant is 1 in metres and seconds
+const NATURAL_MASS: f64 = 1f64 / GRAVITATIONAL_CONSTANT;
+
+// Values of these magnitudes are formatted in the fixed-point notation, the others in the scientific one.
+const FIXED_POINT_MAGNITUDE_MIN: f64 = 1e-2;
+const FIXED_POINT_MAGNITUDE_MAX: f64 = 1e6;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Quantity {
+    Length,
+    Mass,
+    Time,
+    Velocity,
+    Energy,
+    AngularMomentum,
+}
+
+impl Quantity {
+    pub fn dimension(&self) -> Dimension {
+        Dimension(match self {
+            Self::Length => [1f64, 0f64, 0f64],
+            Self::Mass => [0f64, 1f64, 0f64],
+            Self::Time => [0f64, 0f64, 1f64],
+            Self::Velocity => [1f64, 0f64, -1f64],
+            Self::Energy => [2f64, 1f64, -2f64],
+            Self::AngularMomentum => [2f64, 1f64, -1f64],
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Unit {
+    pub(crate) symbol: &'static str,
+    /// The value of the unit in the SI units.
+    pub(crate) factor: f64,
+}
+
+impl Unit {
+    const fn new(symbol: &'static str, factor: f64) -> Self {
+        Self { symbol, factor }
+    }
+}
+
+/// The units that can be referred to by their symbol in the expressions, of each quantity. Those of the velocities are
+/// written as quotients, e.g. `km/s`.
+const UNITS: [(Quantity, &[Unit]); 4] = [
+    (Quantity::Length, &[
+        Unit::new("m", 1f64),
+        Unit::new("km", 1e3),
+        Unit::new("AU", ASTRONOMICAL_UNIT),
+        Unit::new("ly", LIGHT_YEAR),
+        Unit::new("pc", PARSEC),
+    ]),
+    (Quantity::Mass, &[
+        Unit::new("kg", 1f64),
+        Unit::new("g", 1e-3),
+        Unit::new("t", 1e3),
+        Unit::new("Msun", SOLAR_MASS),
+        Unit::new("Mearth", EARTH_MASS),
+        Unit::new("Mjup", JUPITER_MASS),
+    ]),
+    (Quantity::Time, &[
+        Unit::new("s", 1f64),
+        Unit::new("min", 60f64),
+        Unit::new("h", 3_600f64),
+        Unit::new("d", DAY),
+        Unit::new("yr", YEAR),
+    ]),
+    (Quantity::Energy, &[
+        Unit::new("J", 1f64),
+        Unit::new("kJ", 1e3),
+        Unit::new("MJ", 1e6),
+    ]),
+];
+
+/// The units the values are shown in and the unsuffixed input is read in. The simulation itself always uses the SI
+/// units, a pixel of the canvas being a metre.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub(crate) enum UnitSystem {
+    #[default]
+    Si,
+    /// The astronomical unit, the solar mass and the year.
+    Astronomical,
+    /// Metres and seconds, with the mass unit making the gravitational constant 1.
+    Natural,
+}
+
+impl UnitSystem {
+    pub const ALL: [Self; 3] = [Self::Si, Self::Astronomical, Self::Natural];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Si => "SI",
+            Self::Astronomical => "astronomical",
+            Self::Natural => "natural (G = 1)",
+        }
+    }
+
+    pub fn unit(&self, quantity: Quantity) -> Unit {
+        let (length, mass, time) = match self {
+            Self::Si => (Unit::new("m", 1f64), Unit::new("kg", 1f64), Unit::new("s", 1f64)),
+            Self::Astronomical => (Unit::new("AU", ASTRONOMICAL_UNIT), Unit::new("Msun", SOLAR_MASS), Unit::new("yr", YEAR)),
+            Self::Natural => (Unit::new("m", 1f64), Unit::new("M", NATURAL_MASS), Unit::new("s", 1f64)),
+        };
+
+        match (self, quantity) {
+            (_, Quantity::Length) => length,
+            (_, Quantity::Mass) => mass,
+            (_, Quantity::Time) => time,
+            (Self::Si | Self::Natural, Quantity::Velocity) => Unit::new("m/s", length.factor / time.factor),
+            (Self::Astronomical, Quantity::Velocity) => Unit::new("AU/yr", length.factor / time.factor),
+            (Self::Si, Quantity::Energy) => Unit::new("J", 1f64),
+            (Self::Astronomical, Quantity::Energy) => {
+                Unit::new("Msun·AU²/yr²", mass.factor * (length.factor / time.factor).powi(2))
+            }
+            (Self::Natural, Quantity::Energy) => Unit::new("M·m²/s²", mass.factor),
+            (Self::Si, Quantity::AngularMomentum) => Unit::new("kg·m²/s", 1f64),
+            (Self::Astronomical, Quantity::AngularMomentum) => {
+                Unit::new("Msun·AU²/yr", mass.factor * length.factor.powi(2) / time.factor)
+            }
+            (Self::Natural, Quantity::AngularMomentum) => Unit::new("M·m²/s", mass.factor),
+        }
+    }
+
+    /// Formats the value given in the SI units in the unit of this system, with the unit symbol.
+    pub fn format(&self, value: f64, quantity: Quantity) -> String {
+        let unit = self.unit(quantity);
+        format!("{} {}", format_number(value / unit.factor), unit.symbol)
+    }
+
+    /// Formats the value given in the SI units so that it is parsed back to the same value.
+    pub fn format_input(&self, value: f64, quantity: Quantity) -> String {
+        let unit = self.unit(quantity);
+        if unit.factor == 1f64 {
+            value.to_string()
+        } else {
+            format!("{} {}", value / unit.factor, unit.symbol)
+        }
+    }
+
+    /// Returns the value of the unit with the symbol in the unit of this system of the same quantity, and the
+    /// quantity, e.g. `AU` is 1.496e11 m in the SI system.
+    pub fn unit_value(&self, symbol: &str) -> Option<(f64, Quantity)> {
+        UNITS.iter().find_map(|(quantity, _)| {
+            self.units(*quantity)
+                .find(|unit| unit.symbol == symbol)
+                .map(|unit| (unit.factor / self.unit(*quantity).factor, *quantity))
+        })
+    }
+
+    /// Returns the units of the quantity that can be referred to, including the one of this system.
+    fn units(&self, quantity: Quantity) -> impl Iterator<Item = Unit> {
+        UNITS.iter()
+            .filter(move |(unit_quantity, _)| *unit_quantity == quantity)
+            .flat_map(|(_, units)| units.iter().copied())
+            .chain([self.unit(quantity)])
+    }
+
+    /// Returns the gravitational constant in the units of this system.
+    pub fn gravitational_constant(&self) -> f64 {
+        GRAVITATIONAL_CONSTANT * self.unit(Quantity::Mass).factor * self.unit(Quantity::Time).factor.powi(2)
+            / self.unit(Quantity::Length).factor.powi(3)
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value == 0f64 || (FIXED_POINT_MAGNITUDE_MIN..FIXED_POINT_MAGNITUDE_MAX).contains(&value.abs()) {
+        format!("{value:.2}")
+    } else {
+        format!("{value:.3e}")
+    }
+}
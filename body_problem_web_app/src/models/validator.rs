@@ -0,0 +1,86 @@
+use std::fmt::{Display, Formatter};
+
+use crate::models::expression_scope::ExpressionScope;
+use crate::utils::expression::ExpressionError;
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum ValidationError {
+    NotANumber(ExpressionError),
+    BelowMinimum { min: f64, inclusive: bool },
+    AboveMaximum { max: f64 },
+    NotAnInteger,
+    NegativeMass,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotANumber(error) => write!(f, "not a number: {error}"),
+            Self::BelowMinimum { min, inclusive: true } => write!(f, "must be at least {min}"),
+            Self::BelowMinimum { min, inclusive: false } => write!(f, "must be greater than {min}"),
+            Self::AboveMaximum { max } => write!(f, "must be at most {max}"),
+            Self::NotAnInteger => write!(f, "must be a whole number"),
+            Self::NegativeMass => write!(f, "a mass cannot be negative"),
+        }
+    }
+}
+
+/// Checks the text entered into an input, returning the value it stands for. Any expression of a finite number is
+/// accepted by default, the builder methods add the checks.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub(crate) struct Validator {
+    scope: ExpressionScope,
+    /// The lower bound and whether it is allowed.
+    min: Option<(f64, bool)>,
+    max: Option<f64>,
+    integer: bool,
+    mass: bool,
+}
+
+impl Validator {
+    pub fn new(scope: ExpressionScope) -> Self {
+        Self { scope, ..Self::default() }
+    }
+
+    pub fn at_least(self, min: f64) -> Self {
+        Self { min: Some((min, true)), ..self }
+    }
+
+    pub fn greater_than(self, min: f64) -> Self {
+        Self { min: Some((min, false)), ..self }
+    }
+
+    pub fn at_most(self, max: f64) -> Self {
+        Self { max: Some(max), ..self }
+    }
+
+    pub fn integer(self) -> Self {
+        Self { integer: true, ..self }
+    }
+
+    /// Rejects negative values as masses.
+    pub fn mass(self) -> Self {
+        Self { mass: true, ..self }
+    }
+
+    pub fn validate(&self, text: &str) -> Result<f64, ValidationError> {
+        let value = self.scope.evaluate(text).map_err(ValidationError::NotANumber)?;
+
+        if self.mass && value < 0f64 {
+            return Err(ValidationError::NegativeMass);
+        }
+        if let Some((min, inclusive)) = self.min {
+            if value < min || (!inclusive && value == min) {
+                return Err(ValidationError::BelowMinimum { min, inclusive });
+            }
+        }
+        if let Some(max) = self.max.filter(|&max| value > max) {
+            return Err(ValidationError::AboveMaximum { max });
+        }
+        if self.integer && value.fract() != 0f64 {
+            return Err(ValidationError::NotAnInteger);
+        }
+
+        Ok(value)
+    }
+}
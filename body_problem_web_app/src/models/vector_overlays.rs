@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+pub(crate) const VECTOR_OVERLAYS_DEFAULT: VectorOverlays = VectorOverlays::new(false, 0.5f64, false, 1f64, false);
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct VectorOverlays {
+    pub(crate) velocity_shown: bool,
+    /// The length of an arrow in pixels per 1 px/s of the velocity.
+    pub(crate) velocity_scale: f64,
+    pub(crate) acceleration_shown: bool,
+    /// The length of an arrow in pixels per 1 px/s² of the acceleration.
+    pub(crate) acceleration_scale: f64,
+    pub(crate) force_lines_shown: bool,
+}
+
+impl VectorOverlays {
+    pub const fn new(velocity_shown: bool, velocity_scale: f64, acceleration_shown: bool, acceleration_scale: f64, force_lines_shown: bool) -> Self {
+        Self { velocity_shown, velocity_scale, acceleration_shown, acceleration_scale, force_lines_shown }
+    }
+}
+
+impl Default for VectorOverlays {
+    fn default() -> Self {
+        VECTOR_OVERLAYS_DEFAULT
+    }
+}
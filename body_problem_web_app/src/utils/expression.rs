@@ -0,0 +1,440 @@
+use std::f64::consts::{E, PI};
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Provides the values of the names an expression refers to, besides the built-in constants and functions.
+pub(crate) trait ExpressionNames {
+    /// Returns `None` if there is no such variable.
+    fn variable(&self, name: &str) -> Option<Result<Value, ExpressionError>>;
+
+    /// Returns `None` if there is no such function.
+    fn function(&self, name: &str, arguments: &[Value]) -> Option<Result<Value, ExpressionError>>;
+}
+
+/// The exponents of the length, the mass and the time in the unit of a value, e.g. `[1, 0, -1]` for a velocity.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub(crate) struct Dimension(pub(crate) [f64; 3]);
+
+impl Dimension {
+    pub(crate) const NONE: Self = Self([0f64; 3]);
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    fn combined(self, other: Self, sign: f64) -> Self {
+        Self([0, 1, 2].map(|index| self.0[index] + sign * other.0[index]))
+    }
+
+    fn powf(self, exponent: f64) -> Self {
+        // rounded so that e.g. a cube root of a cube is the value's dimension exactly
+        Self(self.0.map(|value| (value * exponent * 1e9).round() / 1e9))
+    }
+}
+
+/// A number in the units of the unit system the names are given in, with the dimension of its unit. The numbers
+/// typed without a unit have none, and are taken to be in the unit of whatever they are added to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Value {
+    pub(crate) number: f64,
+    pub(crate) dimension: Dimension,
+}
+
+impl Value {
+    pub const fn new(number: f64, dimension: Dimension) -> Self {
+        Self { number, dimension }
+    }
+
+    pub const fn dimensionless(number: f64) -> Self {
+        Self::new(number, Dimension::NONE)
+    }
+
+    /// Returns the sum or, if the sign is -1, the difference of the values, unless their units differ.
+    fn add(self, other: Self, sign: f64) -> Result<Self, ExpressionError> {
+        let dimension = if self.dimension.is_none() {
+            other.dimension
+        } else if other.dimension.is_none() || self.dimension == other.dimension {
+            self.dimension
+        } else {
+            return Err(ExpressionError::UnitMismatch);
+        };
+        Ok(Self::new(self.number + sign * other.number, dimension))
+    }
+
+    fn multiply(self, other: Self) -> Self {
+        Self::new(self.number * other.number, self.dimension.combined(other.dimension, 1f64))
+    }
+
+    fn divide(self, other: Self) -> Self {
+        Self::new(self.number / other.number, self.dimension.combined(other.dimension, -1f64))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum ExpressionError {
+    Empty,
+    UnexpectedCharacter(char),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    ArgumentCount { function: String, expected: usize },
+    NoSuchBody(usize),
+    /// The function needs the body the expression is entered for.
+    NoCurrentBody(String),
+    /// The unit of the result is not one of the quantity of the value.
+    WrongUnit,
+    /// Values in units of different quantities are added, subtracted or compared.
+    UnitMismatch,
+    /// The function or the operator only takes values without a unit.
+    UnitNotAllowed(String),
+    NotFinite,
+}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "enter a value"),
+            Self::UnexpectedCharacter(character) => write!(f, "unexpected character '{character}'"),
+            Self::UnexpectedToken(token) => write!(f, "unexpected '{token}'"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of the expression"),
+            Self::UnknownVariable(name) => write!(f, "unknown name '{name}'"),
+            Self::UnknownFunction(name) => write!(f, "unknown function '{name}'"),
+            Self::ArgumentCount { function, expected } => write!(f, "{function} takes {expected} argument(s)"),
+            Self::NoSuchBody(index) => write!(f, "there is no body {index}"),
+            Self::NoCurrentBody(function) => write!(f, "{function} can only be used for a body"),
+            Self::WrongUnit => write!(f, "wrong unit for this field"),
+            Self::UnitMismatch => write!(f, "the units of the terms differ"),
+            Self::UnitNotAllowed(function) => write!(f, "{function} takes values without a unit"),
+            Self::NotFinite => write!(f, "the result is not a finite number"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(char),
+    ParenthesisLeft,
+    ParenthesisRight,
+    Comma,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Identifier(name) => write!(f, "{name}"),
+            Self::Operator(operator) => write!(f, "{operator}"),
+            Self::ParenthesisLeft => write!(f, "("),
+            Self::ParenthesisRight => write!(f, ")"),
+            Self::Comma => write!(f, ","),
+        }
+    }
+}
+
+/// Evaluates an arithmetic expression of numbers, `+ - * / ^`, parentheses, the constants `pi` and `e`, the functions
+/// `sqrt`, `abs`, `exp`, `ln`, `log10`, `sin`, `cos`, `tan`, `min` and `max`, and the names provided. A number or a
+/// name directly followed by another one is multiplied by it, so `5 AU` is `5 * AU` and `3 km/s` is `3 * km / s`.
+pub(crate) fn evaluate(text: &str, names: &impl ExpressionNames) -> Result<Value, ExpressionError> {
+    let tokens = tokenize(text)?;
+    if tokens.is_empty() {
+        return Err(ExpressionError::Empty);
+    }
+
+    let mut parser = Parser { tokens, position: 0, names };
+    let value = parser.sum()?;
+    if let Some(token) = parser.peek() {
+        return Err(ExpressionError::UnexpectedToken(token.to_string()));
+    }
+
+    if value.number.is_finite() {
+        Ok(value)
+    } else {
+        Err(ExpressionError::NotFinite)
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut characters = text.chars().peekable();
+
+    while let Some(&character) = characters.peek() {
+        match character {
+            _ if character.is_whitespace() => {
+                characters.next();
+            }
+            '0'..='9' | '.' => tokens.push(Token::Number(number(&mut characters)?)),
+            _ if character.is_alphabetic() || character == '_' => {
+                let mut name = String::new();
+                while let Some(&character) = characters.peek().filter(|character| character.is_alphanumeric() || **character == '_') {
+                    name.push(character);
+                    characters.next();
+                }
+                tokens.push(Token::Identifier(name));
+            }
+            '+' | '-' | '*' | '/' | '^' => {
+                tokens.push(Token::Operator(character));
+                characters.next();
+            }
+            '(' => {
+                tokens.push(Token::ParenthesisLeft);
+                characters.next();
+            }
+            ')' => {
+                tokens.push(Token::ParenthesisRight);
+                characters.next();
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                characters.next();
+            }
+            _ => return Err(ExpressionError::UnexpectedCharacter(character)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn number(characters: &mut Peekable<Chars>) -> Result<f64, ExpressionError> {
+    let mut text = String::new();
+    while let Some(&character) = characters.peek().filter(|character| character.is_ascii_digit() || **character == '.') {
+        text.push(character);
+        characters.next();
+    }
+
+    // The exponent is only taken if digits follow, otherwise `e` is the constant multiplied by the number.
+    let mut lookahead = characters.clone();
+    if lookahead.next_if(|character| *character == 'e' || *character == 'E').is_some() {
+        let sign = lookahead.next_if(|character| *character == '+' || *character == '-');
+        if lookahead.peek().is_some_and(|character| character.is_ascii_digit()) {
+            text.push('e');
+            text.extend(sign);
+            while let Some(character) = lookahead.next_if(|character| character.is_ascii_digit()) {
+                text.push(character);
+            }
+            *characters = lookahead;
+        }
+    }
+
+    text.parse::<f64>().map_err(|_| ExpressionError::UnexpectedToken(text))
+}
+
+struct Parser<'a, N: ExpressionNames> {
+    tokens: Vec<Token>,
+    position: usize,
+    names: &'a N,
+}
+
+impl<N: ExpressionNames> Parser<'_, N> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, ExpressionError> {
+        let token = self.tokens.get(self.position).cloned().ok_or(ExpressionError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        let token = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(ExpressionError::UnexpectedToken(token.to_string()))
+        }
+    }
+
+    fn sum(&mut self) -> Result<Value, ExpressionError> {
+        let mut value = self.product()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek().cloned() {
+            self.position += 1;
+            let operand = self.product()?;
+            value = value.add(operand, if operator == '+' { 1f64 } else { -1f64 })?;
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<Value, ExpressionError> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Operator('*')) => {
+                    self.position += 1;
+                    value = value.multiply(self.unary()?);
+                }
+                Some(Token::Operator('/')) => {
+                    self.position += 1;
+                    value = value.divide(self.unary()?);
+                }
+                Some(Token::Number(_) | Token::Identifier(_) | Token::ParenthesisLeft) => {
+                    value = value.multiply(self.power()?);
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Value, ExpressionError> {
+        match self.peek() {
+            Some(Token::Operator('-')) => {
+                self.position += 1;
+                let value = self.unary()?;
+                Ok(Value::new(-value.number, value.dimension))
+            }
+            Some(Token::Operator('+')) => {
+                self.position += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Value, ExpressionError> {
+        let base = self.atom()?;
+        if let Some(Token::Operator('^')) = self.peek() {
+            self.position += 1;
+            let exponent = self.unary()?;
+            if !exponent.dimension.is_none() {
+                return Err(ExpressionError::UnitNotAllowed("^".to_string()));
+            }
+            Ok(Value::new(base.number.powf(exponent.number), base.dimension.powf(exponent.number)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Value, ExpressionError> {
+        match self.next()? {
+            Token::Number(number) => Ok(Value::dimensionless(number)),
+            Token::ParenthesisLeft => {
+                let value = self.sum()?;
+                self.expect(Token::ParenthesisRight)?;
+                Ok(value)
+            }
+            Token::Identifier(name) if self.peek() == Some(&Token::ParenthesisLeft) => {
+                self.position += 1;
+                let mut arguments = Vec::new();
+                if self.peek() != Some(&Token::ParenthesisRight) {
+                    arguments.push(self.sum()?);
+                    while self.peek() == Some(&Token::Comma) {
+                        self.position += 1;
+                        arguments.push(self.sum()?);
+                    }
+                }
+                self.expect(Token::ParenthesisRight)?;
+                self.call(&name, &arguments)
+            }
+            Token::Identifier(name) => match name.as_str() {
+                "pi" => Ok(Value::dimensionless(PI)),
+                "e" => Ok(Value::dimensionless(E)),
+                _ => self.names.variable(&name).unwrap_or(Err(ExpressionError::UnknownVariable(name))),
+            },
+            token => Err(ExpressionError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    fn call(&self, name: &str, arguments: &[Value]) -> Result<Value, ExpressionError> {
+        let unary = |function: fn(f64) -> f64, exponent: Option<f64>| match arguments {
+            // The functions other than the powers only take values without a unit.
+            [argument] => match exponent {
+                Some(exponent) => Ok(Value::new(function(argument.number), argument.dimension.powf(exponent))),
+                None if argument.dimension.is_none() => Ok(Value::dimensionless(function(argument.number))),
+                None => Err(ExpressionError::UnitNotAllowed(name.to_string())),
+            },
+            _ => Err(ExpressionError::ArgumentCount { function: name.to_string(), expected: 1 }),
+        };
+        let binary = |function: fn(f64, f64) -> f64| match arguments {
+            // compared like in a sum
+            [first, second] => first.add(*second, 0f64)
+                .map(|value| Value::new(function(first.number, second.number), value.dimension)),
+            _ => Err(ExpressionError::ArgumentCount { function: name.to_string(), expected: 2 }),
+        };
+
+        match name {
+            "sqrt" => unary(f64::sqrt, Some(0.5)),
+            "abs" => unary(f64::abs, Some(1f64)),
+            "exp" => unary(f64::exp, None),
+            "ln" => unary(f64::ln, None),
+            "log10" => unary(f64::log10, None),
+            "sin" => unary(f64::sin, None),
+            "cos" => unary(f64::cos, None),
+            "tan" => unary(f64::tan, None),
+            "min" => binary(f64::min),
+            "max" => binary(f64::max),
+            _ => self.names.function(name, arguments).unwrap_or(Err(ExpressionError::UnknownFunction(name.to_string()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LENGTH: Dimension = Dimension([1f64, 0f64, 0f64]);
+    const TIME: Dimension = Dimension([0f64, 0f64, 1f64]);
+
+    /// The units `km` and `s` and the function `double`.
+    struct Names;
+
+    impl ExpressionNames for Names {
+        fn variable(&self, name: &str) -> Option<Result<Value, ExpressionError>> {
+            match name {
+                "km" => Some(Ok(Value::new(1e3, LENGTH))),
+                "s" => Some(Ok(Value::new(1f64, TIME))),
+                _ => None,
+            }
+        }
+
+        fn function(&self, name: &str, arguments: &[Value]) -> Option<Result<Value, ExpressionError>> {
+            (name == "double").then(|| Ok(Value::new(2f64 * arguments[0].number, arguments[0].dimension)))
+        }
+    }
+
+    fn number(text: &str) -> f64 {
+        evaluate(text, &Names).unwrap().number
+    }
+
+    #[test]
+    fn arithmetic_follows_the_precedence() {
+        assert_eq!(number("1 + 2 * 3"), 7f64);
+        assert_eq!(number("(1 + 2) * 3"), 9f64);
+        assert_eq!(number("-2^2"), -4f64);
+        assert_eq!(number("2^3^2"), 512f64);
+        assert_eq!(number("1.5e3 + max(1, 2)"), 1502f64);
+        assert_eq!(number("2 pi"), 2f64 * PI);
+        assert_eq!(number("double(3)"), 6f64);
+    }
+
+    #[test]
+    fn quotients_of_units_are_divided() {
+        assert_eq!(evaluate(" 30 km/s", &Names), Ok(Value::new(3e4, Dimension([1f64, 0f64, -1f64]))));
+        assert_eq!(evaluate("sqrt(4 km^2)", &Names), Ok(Value::new(2e3, LENGTH)));
+        assert_eq!(evaluate("(8 km^3)^(1/3)", &Names).map(|value| value.dimension), Ok(LENGTH));
+    }
+
+    #[test]
+    fn unitless_numbers_take_the_unit_of_the_terms() {
+        assert_eq!(evaluate("2 km + 3", &Names), Ok(Value::new(2003f64, LENGTH)));
+        assert_eq!(evaluate("min(1, 2 s)", &Names), Ok(Value::new(1f64, TIME)));
+    }
+
+    #[test]
+    fn units_are_checked() {
+        assert_eq!(evaluate("1 km + 1 s", &Names), Err(ExpressionError::UnitMismatch));
+        assert_eq!(evaluate("sin(1 km)", &Names), Err(ExpressionError::UnitNotAllowed("sin".to_string())));
+        assert_eq!(evaluate("2^(1 s)", &Names), Err(ExpressionError::UnitNotAllowed("^".to_string())));
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        assert_eq!(evaluate("", &Names), Err(ExpressionError::Empty));
+        assert_eq!(evaluate("1 +", &Names), Err(ExpressionError::UnexpectedEnd));
+        assert_eq!(evaluate("1 $", &Names), Err(ExpressionError::UnexpectedCharacter('$')));
+        assert_eq!(evaluate("foo", &Names), Err(ExpressionError::UnknownVariable("foo".to_string())));
+        assert_eq!(evaluate("1 / 0", &Names), Err(ExpressionError::NotFinite));
+    }
+}
@@ -1,6 +1,12 @@
+pub(crate) mod expression;
+pub(crate) mod trajectories;
+pub(crate) mod webgl_renderer;
+
 use gloo_events::EventListener;
+use gloo_utils::document;
 use gloo_utils::format::JsValueSerdeExt;
-use web_sys::{CanvasRenderingContext2d, Event, HtmlCanvasElement, window};
+use web_sys::{Blob, BlobPropertyBag, CanvasRenderingContext2d, Event, HtmlAnchorElement, HtmlCanvasElement, Url, window};
+use web_sys::js_sys::{Array, Uint8Array};
 use web_sys::wasm_bindgen::{JsCast, JsValue};
 use yew::{Callback, UseStateHandle};
 
@@ -51,3 +57,17 @@ impl SimulationCanvasInitialize for HtmlCanvasElement {
         )
     }
 }
+
+/// Lets the browser download the data as a file with the name.
+pub(crate) fn download(file_name: &str, media_type: &str, data: &[u8]) -> Result<(), JsValue> {
+    let parts = Array::of1(&Uint8Array::from(data));
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, BlobPropertyBag::new().type_(media_type))?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let anchor = document().create_element("a")?.unchecked_into::<HtmlAnchorElement>();
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
@@ -0,0 +1,386 @@
+use std::cell::OnceCell;
+use std::collections::VecDeque;
+use std::ops::{RangeFrom, RangeToInclusive};
+use std::time::Duration;
+
+use nalgebra::Vector2;
+use web_sys::{CanvasRenderingContext2d, Path2d};
+
+use crate::models::trajectory_segment::TrajectorySegment;
+
+/// How far a body has to move for its position to be recorded again.
+const TRAJECTORY_SEGMENT_LENGTH_MIN: f64 = 0.5f64;
+/// The longest line replacing the points lying almost on it, so that the trajectories are trimmed smoothly.
+const TRAJECTORY_SEGMENT_LENGTH_MAX: f64 = 50f64;
+/// How far from the line replacing them the points may lie, in pixels.
+const TRAJECTORY_DEVIATION_MAX: f64 = 0.2f64;
+/// The number of lines of a chunk of a trajectory whose path is built once.
+const TRAJECTORY_CHUNK_LENGTH: usize = 64;
+/// The most points kept of a single trajectory, the oldest ones being dropped first.
+const TRAJECTORY_POINTS_MAX: usize = 20_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct TrajectoryPoint {
+    pub(crate) position: Vector2<f64>,
+    pub(crate) speed: f64,
+    pub(crate) recorded_after: Duration,
+}
+
+/// The path through consecutive points of a trajectory, the end one being the start of the next chunk.
+struct TrajectoryChunk {
+    /// Built when first stroked, as the points of a chunk do not change.
+    path: OnceCell<Path2d>,
+    /// The number of the first point since the trajectory started.
+    start: usize,
+}
+
+/// The recorded trajectory of a single body, a ring buffer of its positions. A point lying almost on the line between
+/// its neighbours is replaced by the next one, so that straight parts take few points and curved ones many.
+pub(crate) struct Trajectory {
+    points: VecDeque<TrajectoryPoint>,
+    /// The number of points dropped from the front, which the points are numbered after.
+    points_dropped: usize,
+    /// The positions replaced by the last point since the one before it, which its line has to stay close to.
+    positions_replaced: Vec<Vector2<f64>>,
+    chunks: VecDeque<TrajectoryChunk>,
+    /// The number of the first point not covered by a chunk but the end one.
+    chunked_until: usize,
+}
+
+impl Trajectory {
+    fn new() -> Self {
+        Self { points: VecDeque::new(), points_dropped: 0, positions_replaced: Vec::new(), chunks: VecDeque::new(), chunked_until: 0 }
+    }
+
+    pub fn points(&self) -> &VecDeque<TrajectoryPoint> {
+        &self.points
+    }
+
+    /// Returns the position at the time, interpolated between the recorded ones.
+    pub fn position_at(&self, recorded_after: Duration) -> Option<Vector2<f64>> {
+        let index = self.points.partition_point(|point| point.recorded_after < recorded_after);
+        match (index.checked_sub(1).and_then(|index| self.points.get(index)), self.points.get(index)) {
+            (Some(before), Some(after)) => {
+                let ratio = (recorded_after - before.recorded_after).as_secs_f64()
+                    / (after.recorded_after - before.recorded_after).as_secs_f64();
+                Some(before.position.lerp(&after.position, ratio))
+            }
+            (Some(point), None) | (None, Some(point)) => Some(point.position),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns whether the point changed the trajectory.
+    fn record(&mut self, point: TrajectoryPoint) -> bool {
+        let Some(last) = self.points.back() else {
+            self.points.push_back(point);
+            return true;
+        };
+        if (point.position - last.position).norm() <= TRAJECTORY_SEGMENT_LENGTH_MIN {
+            return false;
+        }
+
+        // Only the last point is replaced, which no chunk ends at.
+        let before_last = self.points.len().checked_sub(2).map(|index| self.points[index]);
+        if let Some(before_last) = before_last.filter(|_| self.points.len() - 1 + self.points_dropped > self.chunked_until) {
+            let deviation_max = self.positions_replaced.iter()
+                .chain([last.position].iter())
+                .map(|position| distance_to_line(position, &before_last.position, &point.position))
+                .fold(0f64, f64::max);
+            if deviation_max <= TRAJECTORY_DEVIATION_MAX
+                && (point.position - before_last.position).norm() <= TRAJECTORY_SEGMENT_LENGTH_MAX {
+                self.positions_replaced.push(last.position);
+                *self.points.back_mut().unwrap() = point;
+                return true;
+            }
+        }
+
+        self.positions_replaced.clear();
+        self.points.push_back(point);
+
+        // The chunk ends before the last point, as that can still be replaced.
+        if self.points_dropped + self.points.len() - 1 > self.chunked_until + TRAJECTORY_CHUNK_LENGTH {
+            self.chunks.push_back(TrajectoryChunk { path: OnceCell::new(), start: self.chunked_until });
+            self.chunked_until += TRAJECTORY_CHUNK_LENGTH;
+        }
+
+        if self.points.len() > TRAJECTORY_POINTS_MAX {
+            self.drop_first();
+        }
+
+        true
+    }
+
+    /// Drops the points recorded before the time, returning whether there were any.
+    fn trim(&mut self, recorded_after_min: Duration) -> bool {
+        let mut trimmed = false;
+        while self.points.front().is_some_and(|point| point.recorded_after < recorded_after_min) {
+            self.drop_first();
+            trimmed = true;
+        }
+        trimmed
+    }
+
+    fn drop_first(&mut self) {
+        self.points.pop_front();
+        self.points_dropped += 1;
+        self.chunked_until = self.chunked_until.max(self.points_dropped);
+        // The rest of a chunk partially dropped is drawn point by point.
+        while self.chunks.front().is_some_and(|chunk| chunk.start < self.points_dropped) {
+            self.chunks.pop_front();
+        }
+    }
+
+    /// Returns the indices of the points before the first chunk, the front of the trajectory being trimmed, and of
+    /// those after the last one.
+    fn unchunked(&self) -> (RangeToInclusive<usize>, RangeFrom<usize>) {
+        let head_end = self.chunks.front().map_or(self.chunked_until, |chunk| chunk.start);
+        (..=head_end - self.points_dropped, self.chunked_until - self.points_dropped..)
+    }
+
+    /// Strokes the trajectory with the current style of the context, using the paths of the chunks.
+    pub fn stroke(&self, context: &CanvasRenderingContext2d) {
+        if self.points.is_empty() {
+            return;
+        }
+
+        let (head, tail) = self.unchunked();
+        stroke_polyline(context, self.points.range(head));
+        for chunk in self.chunks.iter() {
+            let path = chunk.path.get_or_init(|| {
+                let path = Path2d::new().unwrap();
+                let start = chunk.start - self.points_dropped;
+                for (index, point) in self.points.range(start..=start + TRAJECTORY_CHUNK_LENGTH).enumerate() {
+                    if index == 0 {
+                        path.move_to(point.position.x, -point.position.y);
+                    } else {
+                        path.line_to(point.position.x, -point.position.y);
+                    }
+                }
+                path
+            });
+            context.stroke_with_path(path);
+        }
+        stroke_polyline(context, self.points.range(tail));
+    }
+}
+
+/// The trajectories of all the bodies.
+pub(crate) struct Trajectories {
+    trajectories: Vec<Trajectory>,
+    recorded_after_last: Option<Duration>,
+}
+
+impl Trajectories {
+    pub fn new() -> Self {
+        Self { trajectories: Vec::new(), recorded_after_last: None }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Trajectory> {
+        self.trajectories.get(index)
+    }
+
+    pub fn recorded_after_last(&self) -> Option<Duration> {
+        self.recorded_after_last
+    }
+
+    /// Records the positions of the bodies, returning whether any trajectory changed.
+    pub fn record(&mut self, segment: &TrajectorySegment) -> bool {
+        // The bodies only change with edits, which start the trajectories over.
+        if self.trajectories.len() != segment.positions.len() {
+            self.trajectories = segment.positions.iter().map(|_| Trajectory::new()).collect();
+        }
+        self.recorded_after_last = Some(segment.recorded_after);
+
+        let mut changed = false;
+        for (trajectory, (&position, &speed)) in self.trajectories.iter_mut().zip(segment.positions.iter().zip(segment.speeds.iter())) {
+            changed |= trajectory.record(TrajectoryPoint { position, speed, recorded_after: segment.recorded_after });
+        }
+        changed
+    }
+
+    /// Drops the points recorded before the time, returning whether there were any.
+    pub fn trim(&mut self, recorded_after_min: Duration) -> bool {
+        let mut trimmed = false;
+        for trajectory in self.trajectories.iter_mut() {
+            trimmed |= trajectory.trim(recorded_after_min);
+        }
+        trimmed
+    }
+}
+
+fn stroke_polyline<'a>(context: &CanvasRenderingContext2d, points: impl IntoIterator<Item = &'a TrajectoryPoint>) {
+    let mut points = points.into_iter();
+    let Some(start) = points.next() else {
+        return;
+    };
+
+    context.begin_path();
+    context.move_to(start.position.x, -start.position.y);
+    let mut lines = 0;
+    for point in points {
+        context.line_to(point.position.x, -point.position.y);
+        lines += 1;
+    }
+    if lines > 0 {
+        context.stroke();
+    }
+}
+
+fn distance_to_line(position: &Vector2<f64>, start: &Vector2<f64>, end: &Vector2<f64>) -> f64 {
+    let direction = end - start;
+    let length = direction.norm();
+    if length == 0f64 {
+        (position - start).norm()
+    } else {
+        direction.perp(&(position - start)).abs() / length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64, recorded_after: f64) -> TrajectoryPoint {
+        TrajectoryPoint { position: Vector2::new(x, y), speed: 1f64, recorded_after: Duration::from_secs_f64(recorded_after) }
+    }
+
+    /// Records a zigzag, none of whose points lie on the line between their neighbours, a point per second.
+    fn zigzag(trajectory: &mut Trajectory, count: usize) {
+        for index in 0..count {
+            zigzag_on(trajectory, index);
+        }
+    }
+
+    fn zigzag_on(trajectory: &mut Trajectory, index: usize) {
+        assert!(trajectory.record(point(index as f64, (index % 2) as f64, index as f64)));
+    }
+
+    /// Checks that the lines before, in and after the chunks are each the line between two consecutive points.
+    fn assert_lines_covered(trajectory: &Trajectory) {
+        let (head, tail) = trajectory.unchunked();
+        let mut lines = (0..head.end).collect::<Vec<_>>();
+        for chunk in trajectory.chunks.iter() {
+            let start = chunk.start - trajectory.points_dropped;
+            lines.extend(start..start + TRAJECTORY_CHUNK_LENGTH);
+        }
+        lines.extend(tail.start..trajectory.points.len() - 1);
+
+        assert_eq!(lines, (0..trajectory.points.len() - 1).collect::<Vec<_>>());
+        assert!(trajectory.chunked_until >= trajectory.points_dropped);
+    }
+
+    #[test]
+    fn points_close_to_the_last_one_are_skipped() {
+        let mut trajectory = Trajectory::new();
+
+        assert!(trajectory.record(point(0f64, 0f64, 0f64)));
+        assert!(!trajectory.record(point(0.3, 0.3, 1f64)));
+        assert!(trajectory.record(point(1f64, 0f64, 2f64)));
+
+        assert_eq!(trajectory.points().len(), 2);
+    }
+
+    #[test]
+    fn points_on_a_line_are_replaced_up_to_the_longest_line() {
+        let mut trajectory = Trajectory::new();
+        for index in 0..=100 {
+            assert!(trajectory.record(point(index as f64, 0f64, index as f64)));
+        }
+
+        let positions = trajectory.points().iter().map(|point| point.position.x).collect::<Vec<_>>();
+        assert_eq!(positions, [0f64, 50f64, 100f64]);
+    }
+
+    #[test]
+    fn points_off_the_line_are_kept() {
+        let mut trajectory = Trajectory::new();
+        zigzag(&mut trajectory, 10);
+
+        assert_eq!(trajectory.points().len(), 10);
+        assert_eq!(trajectory.position_at(Duration::from_secs_f64(2.5)), Some(Vector2::new(2.5, 0.5)));
+        assert_eq!(trajectory.position_at(Duration::from_secs(20)), Some(Vector2::new(9f64, 1f64)));
+    }
+
+    #[test]
+    fn lines_are_chunked_all_but_the_last_one() {
+        let mut trajectory = Trajectory::new();
+        zigzag(&mut trajectory, TRAJECTORY_CHUNK_LENGTH + 1);
+        assert!(trajectory.chunks.is_empty());
+        assert_lines_covered(&trajectory);
+
+        // The chunk ends before the last point, which can still be replaced.
+        zigzag_on(&mut trajectory, TRAJECTORY_CHUNK_LENGTH + 1);
+        assert_eq!(trajectory.chunks.len(), 1);
+        assert_eq!(trajectory.chunked_until, TRAJECTORY_CHUNK_LENGTH);
+        let length = TRAJECTORY_CHUNK_LENGTH as f64;
+        assert!(trajectory.record(point(length + 2f64, 2f64, length + 2f64)));
+        assert_eq!(trajectory.points().len(), TRAJECTORY_CHUNK_LENGTH + 2);
+        assert_eq!(trajectory.points().back().unwrap().position, Vector2::new(length + 2f64, 2f64));
+        assert_lines_covered(&trajectory);
+
+        let mut trajectory = Trajectory::new();
+        zigzag(&mut trajectory, 3 * TRAJECTORY_CHUNK_LENGTH + 2);
+        assert_eq!(trajectory.chunks.len(), 3);
+        assert!(trajectory.chunks.iter().enumerate().all(|(index, chunk)| chunk.start == index * TRAJECTORY_CHUNK_LENGTH));
+        assert_eq!(trajectory.chunked_until, 3 * TRAJECTORY_CHUNK_LENGTH);
+        assert_lines_covered(&trajectory);
+    }
+
+    #[test]
+    fn trimming_drops_the_points_and_the_chunks_before() {
+        let mut trajectory = Trajectory::new();
+        zigzag(&mut trajectory, 5 * TRAJECTORY_CHUNK_LENGTH);
+
+        for trimmed_until in [1, TRAJECTORY_CHUNK_LENGTH - 1, TRAJECTORY_CHUNK_LENGTH, 3 * TRAJECTORY_CHUNK_LENGTH + 10] {
+            assert!(trajectory.trim(Duration::from_secs(trimmed_until as u64)));
+
+            assert_eq!(trajectory.points_dropped, trimmed_until);
+            assert_eq!(trajectory.points().front().unwrap().recorded_after, Duration::from_secs(trimmed_until as u64));
+            assert!(trajectory.chunks.iter().all(|chunk| chunk.start >= trimmed_until));
+            assert_lines_covered(&trajectory);
+        }
+        assert!(!trajectory.trim(Duration::from_secs(3 * TRAJECTORY_CHUNK_LENGTH as u64 + 10)));
+
+        // recording on after the trimming
+        for index in 5 * TRAJECTORY_CHUNK_LENGTH..7 * TRAJECTORY_CHUNK_LENGTH {
+            zigzag_on(&mut trajectory, index);
+        }
+        assert_lines_covered(&trajectory);
+    }
+
+    #[test]
+    fn oldest_points_are_dropped_beyond_the_most_kept() {
+        let mut trajectory = Trajectory::new();
+        zigzag(&mut trajectory, TRAJECTORY_POINTS_MAX + 100);
+
+        assert_eq!(trajectory.points().len(), TRAJECTORY_POINTS_MAX);
+        assert_eq!(trajectory.points_dropped, 100);
+        assert_lines_covered(&trajectory);
+    }
+
+    #[test]
+    fn trajectories_start_over_with_other_bodies() {
+        let segment = |count: usize, recorded_after: f64| TrajectorySegment {
+            positions: (0..count).map(|index| Vector2::new(index as f64, recorded_after)).collect(),
+            speeds: vec![1f64; count],
+            recorded_after: Duration::from_secs_f64(recorded_after),
+        };
+        let mut trajectories = Trajectories::new();
+
+        assert!(trajectories.record(&segment(2, 0f64)));
+        assert!(trajectories.record(&segment(2, 1f64)));
+        assert_eq!(trajectories.get(1).unwrap().points().len(), 2);
+        assert!(trajectories.record(&segment(3, 2f64)));
+        assert_eq!(trajectories.get(1).unwrap().points().len(), 1);
+        assert!(trajectories.get(2).is_some());
+        assert_eq!(trajectories.recorded_after_last(), Some(Duration::from_secs(2)));
+
+        assert!(trajectories.trim(Duration::from_secs(3)));
+        assert!(trajectories.get(0).unwrap().points().is_empty());
+    }
+}
@@ -0,0 +1,311 @@
+use nalgebra::Vector2;
+use web_sys::{HtmlCanvasElement, WebGl2RenderingContext, WebGlBuffer, WebGlProgram, WebGlShader, WebGlUniformLocation, WebGlVertexArrayObject, window};
+use web_sys::js_sys::Float32Array;
+use web_sys::wasm_bindgen::JsCast;
+use web_time::Duration;
+
+use crate::models::rendered_body::RenderedBody;
+
+type Gl = WebGl2RenderingContext;
+
+/// The maximum number of points kept for the trail of each body.
+const TRAIL_CAPACITY: usize = 8192;
+// position x, position y, recorded after
+const TRAIL_VERTEX_FLOATS: usize = 3;
+// center x, center y, radius, red, green, blue
+const BODY_INSTANCE_FLOATS: usize = 6;
+const TRAJECTORY_MAX_SEGMENT_LENGTH: f64 = 0.5f64;
+
+const BODY_VERTEX_SHADER: &str = r#"#version 300 es
+layout(location = 0) in vec2 corner;
+layout(location = 1) in vec2 center;
+layout(location = 2) in float radius;
+layout(location = 3) in vec3 color;
+uniform vec2 viewport_half;
+out vec2 fragment_corner;
+out vec3 fragment_color;
+
+void main() {
+    fragment_corner = corner;
+    fragment_color = color;
+    gl_Position = vec4((center + corner * radius) / viewport_half, 0.0, 1.0);
+}
+"#;
+
+const BODY_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision mediump float;
+in vec2 fragment_corner;
+in vec3 fragment_color;
+out vec4 output_color;
+
+void main() {
+    if (dot(fragment_corner, fragment_corner) > 1.0) {
+        discard;
+    }
+    output_color = vec4(fragment_color, 1.0);
+}
+"#;
+
+const TRAIL_VERTEX_SHADER: &str = r#"#version 300 es
+layout(location = 0) in vec2 position;
+layout(location = 1) in float recorded_after;
+uniform vec2 viewport_half;
+uniform float recorded_after_min;
+out float fragment_visible;
+
+void main() {
+    fragment_visible = recorded_after >= recorded_after_min ? 1.0 : 0.0;
+    gl_Position = vec4(position / viewport_half, 0.0, 1.0);
+}
+"#;
+
+const TRAIL_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision mediump float;
+uniform vec3 color;
+in float fragment_visible;
+out vec4 output_color;
+
+void main() {
+    if (fragment_visible < 1.0) {
+        discard;
+    }
+    output_color = vec4(color, 1.0);
+}
+"#;
+
+/// The trail of a single body, stored in a ring buffer on the GPU. Each point is written twice, at its index and
+/// at the index shifted by the capacity, so that the points from the oldest to the newest are always contiguous.
+#[derive(Clone, Copy, Default)]
+struct TrailRing {
+    start: usize,
+    length: usize,
+}
+
+/// Draws the bodies as instanced sprites and their trails from buffers kept on the GPU.
+pub(crate) struct WebGlRenderer {
+    context: Gl,
+    body_program: WebGlProgram,
+    body_vertex_array: WebGlVertexArrayObject,
+    body_instance_buffer: WebGlBuffer,
+    body_viewport_half: Option<WebGlUniformLocation>,
+    trail_program: WebGlProgram,
+    trail_vertex_array: WebGlVertexArrayObject,
+    trail_buffer: WebGlBuffer,
+    trail_viewport_half: Option<WebGlUniformLocation>,
+    trail_recorded_after_min: Option<WebGlUniformLocation>,
+    trail_color: Option<WebGlUniformLocation>,
+    trail_rings: Vec<TrailRing>,
+    trail_positions_last: Vec<Vector2<f64>>,
+    trail_recorded_after_last: Duration,
+}
+
+impl WebGlRenderer {
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, String> {
+        let context = canvas.get_context("webgl2")
+            .map_err(|_| "failed to get the WebGL 2 context".to_string())?
+            .ok_or("WebGL 2 is not supported")?
+            .dyn_into::<Gl>()
+            .map_err(|_| "failed to get the WebGL 2 context".to_string())?;
+
+        let body_program = link_program(&context, BODY_VERTEX_SHADER, BODY_FRAGMENT_SHADER)?;
+        let trail_program = link_program(&context, TRAIL_VERTEX_SHADER, TRAIL_FRAGMENT_SHADER)?;
+
+        let body_vertex_array = context.create_vertex_array().ok_or("failed to create a vertex array")?;
+        context.bind_vertex_array(Some(&body_vertex_array));
+
+        let corner_buffer = context.create_buffer().ok_or("failed to create a buffer")?;
+        context.bind_buffer(Gl::ARRAY_BUFFER, Some(&corner_buffer));
+        let corners: [f32; 8] = [-1f32, -1f32, 1f32, -1f32, -1f32, 1f32, 1f32, 1f32];
+        context.buffer_data_with_array_buffer_view(Gl::ARRAY_BUFFER, &Float32Array::from(&corners[..]), Gl::STATIC_DRAW);
+        context.enable_vertex_attrib_array(0);
+        context.vertex_attrib_pointer_with_i32(0, 2, Gl::FLOAT, false, 0, 0);
+
+        let body_instance_buffer = context.create_buffer().ok_or("failed to create a buffer")?;
+        context.bind_buffer(Gl::ARRAY_BUFFER, Some(&body_instance_buffer));
+        let stride = (BODY_INSTANCE_FLOATS * size_of::<f32>()) as i32;
+        for (location, size, offset) in [(1, 2, 0), (2, 1, 2), (3, 3, 3)] {
+            context.enable_vertex_attrib_array(location);
+            context.vertex_attrib_pointer_with_i32(location, size, Gl::FLOAT, false, stride, offset * size_of::<f32>() as i32);
+            context.vertex_attrib_divisor(location, 1);
+        }
+
+        let trail_vertex_array = context.create_vertex_array().ok_or("failed to create a vertex array")?;
+        context.bind_vertex_array(Some(&trail_vertex_array));
+
+        let trail_buffer = context.create_buffer().ok_or("failed to create a buffer")?;
+        context.bind_buffer(Gl::ARRAY_BUFFER, Some(&trail_buffer));
+        let stride = (TRAIL_VERTEX_FLOATS * size_of::<f32>()) as i32;
+        for (location, size, offset) in [(0, 2, 0), (1, 1, 2)] {
+            context.enable_vertex_attrib_array(location);
+            context.vertex_attrib_pointer_with_i32(location, size, Gl::FLOAT, false, stride, offset * size_of::<f32>() as i32);
+        }
+
+        context.bind_vertex_array(None);
+
+        Ok(Self {
+            body_viewport_half: context.get_uniform_location(&body_program, "viewport_half"),
+            trail_viewport_half: context.get_uniform_location(&trail_program, "viewport_half"),
+            trail_recorded_after_min: context.get_uniform_location(&trail_program, "recorded_after_min"),
+            trail_color: context.get_uniform_location(&trail_program, "color"),
+            context,
+            body_program,
+            body_vertex_array,
+            body_instance_buffer,
+            trail_program,
+            trail_vertex_array,
+            trail_buffer,
+            trail_rings: Vec::new(),
+            trail_positions_last: Vec::new(),
+            trail_recorded_after_last: Duration::ZERO,
+        })
+    }
+
+    pub fn clear_trails(&mut self) {
+        self.trail_rings.clear();
+        self.trail_positions_last.clear();
+        self.trail_recorded_after_last = Duration::ZERO;
+    }
+
+    pub fn trail_recorded_after_last(&self) -> Duration {
+        self.trail_recorded_after_last
+    }
+
+    /// Appends the positions to the trails if any of the bodies has moved far enough since the last time.
+    pub fn record_trails(&mut self, positions: &[Vector2<f64>], recorded_after: Duration) {
+        // The time went back (e.g. by seeking), so the recorded trails no longer precede it.
+        if recorded_after < self.trail_recorded_after_last {
+            self.clear_trails();
+        }
+
+        if positions.len() != self.trail_rings.len() {
+            self.trail_rings = vec![TrailRing::default(); positions.len()];
+            self.trail_positions_last.clear();
+            self.context.bind_buffer(Gl::ARRAY_BUFFER, Some(&self.trail_buffer));
+            self.context.buffer_data_with_i32(
+                Gl::ARRAY_BUFFER,
+                (positions.len() * 2 * TRAIL_CAPACITY * TRAIL_VERTEX_FLOATS * size_of::<f32>()) as i32,
+                Gl::DYNAMIC_DRAW,
+            );
+        }
+
+        if !self.trail_positions_last.is_empty() && !positions.iter().zip(self.trail_positions_last.iter())
+            .any(|(position, position_last)| (position - position_last).norm() > TRAJECTORY_MAX_SEGMENT_LENGTH) {
+            return;
+        }
+
+        self.context.bind_buffer(Gl::ARRAY_BUFFER, Some(&self.trail_buffer));
+        for (body_index, (position, trail_ring)) in positions.iter().zip(self.trail_rings.iter_mut()).enumerate() {
+            let vertex = Float32Array::from(&[position.x as f32, position.y as f32, recorded_after.as_secs_f32()][..]);
+            let index = (trail_ring.start + trail_ring.length) % TRAIL_CAPACITY;
+            for index in [index, index + TRAIL_CAPACITY] {
+                let offset = (body_index * 2 * TRAIL_CAPACITY + index) * TRAIL_VERTEX_FLOATS * size_of::<f32>();
+                self.context.buffer_sub_data_with_i32_and_array_buffer_view(Gl::ARRAY_BUFFER, offset as i32, &vertex);
+            }
+
+            if trail_ring.length == TRAIL_CAPACITY {
+                trail_ring.start = (trail_ring.start + 1) % TRAIL_CAPACITY;
+            } else {
+                trail_ring.length += 1;
+            }
+        }
+
+        self.trail_positions_last = positions.to_vec();
+        self.trail_recorded_after_last = recorded_after;
+    }
+
+    pub fn draw(&self, rendered_bodies: &[RenderedBody], circle_radii: &[f64], trail_recorded_after_min: Option<Duration>) {
+        let context = &self.context;
+        let canvas = context.canvas().unwrap().unchecked_into::<HtmlCanvasElement>();
+        let (width, height) = (canvas.width() as f32, canvas.height() as f32);
+
+        context.viewport(0, 0, width as i32, height as i32);
+        // transparent to show the field layer beneath
+        context.clear_color(0f32, 0f32, 0f32, 0f32);
+        context.clear(Gl::COLOR_BUFFER_BIT);
+
+        if let Some(trail_recorded_after_min) = trail_recorded_after_min {
+            context.use_program(Some(&self.trail_program));
+            context.uniform2f(self.trail_viewport_half.as_ref(), width / 2f32, height / 2f32);
+            context.uniform1f(self.trail_recorded_after_min.as_ref(), trail_recorded_after_min.as_secs_f32());
+            context.bind_vertex_array(Some(&self.trail_vertex_array));
+
+            // Reversing for a more intuitive layer order.
+            for (body_index, (rendered_body, trail_ring)) in rendered_bodies.iter().zip(self.trail_rings.iter()).enumerate().rev() {
+                let [red, green, blue] = parse_color(&rendered_body.color);
+                context.uniform3f(self.trail_color.as_ref(), red, green, blue);
+                context.draw_arrays(
+                    Gl::LINE_STRIP,
+                    (body_index * 2 * TRAIL_CAPACITY + trail_ring.start) as i32,
+                    trail_ring.length as i32,
+                );
+            }
+        }
+
+        // Reversing for a more intuitive layer order.
+        let instances = rendered_bodies.iter().zip(circle_radii.iter()).rev()
+            .flat_map(|(rendered_body, &circle_radius)| {
+                let [red, green, blue] = parse_color(&rendered_body.color);
+                [
+                    rendered_body.body.position.x as f32,
+                    rendered_body.body.position.y as f32,
+                    circle_radius as f32,
+                    red,
+                    green,
+                    blue,
+                ]
+            })
+            .collect::<Vec<f32>>();
+
+        context.use_program(Some(&self.body_program));
+        context.uniform2f(self.body_viewport_half.as_ref(), width / 2f32, height / 2f32);
+        context.bind_vertex_array(Some(&self.body_vertex_array));
+        context.bind_buffer(Gl::ARRAY_BUFFER, Some(&self.body_instance_buffer));
+        context.buffer_data_with_array_buffer_view(Gl::ARRAY_BUFFER, &Float32Array::from(&instances[..]), Gl::DYNAMIC_DRAW);
+        context.draw_arrays_instanced(Gl::TRIANGLE_STRIP, 0, 4, rendered_bodies.len() as i32);
+
+        context.bind_vertex_array(None);
+    }
+}
+
+/// Whether the browser is able to create a WebGL 2 context.
+pub(crate) fn webgl2_supported() -> bool {
+    window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("canvas").ok())
+        .and_then(|canvas| canvas.unchecked_into::<HtmlCanvasElement>().get_context("webgl2").ok().flatten())
+        .is_some()
+}
+
+fn compile_shader(context: &Gl, shader_type: u32, source: &str) -> Result<WebGlShader, String> {
+    let shader = context.create_shader(shader_type).ok_or("failed to create a shader")?;
+    context.shader_source(&shader, source);
+    context.compile_shader(&shader);
+
+    if context.get_shader_parameter(&shader, Gl::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        Ok(shader)
+    } else {
+        Err(context.get_shader_info_log(&shader).unwrap_or_default())
+    }
+}
+
+fn link_program(context: &Gl, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<WebGlProgram, String> {
+    let program = context.create_program().ok_or("failed to create a program")?;
+    context.attach_shader(&program, &compile_shader(context, Gl::VERTEX_SHADER, vertex_shader_source)?);
+    context.attach_shader(&program, &compile_shader(context, Gl::FRAGMENT_SHADER, fragment_shader_source)?);
+    context.link_program(&program);
+
+    if context.get_program_parameter(&program, Gl::LINK_STATUS).as_bool().unwrap_or(false) {
+        Ok(program)
+    } else {
+        Err(context.get_program_info_log(&program).unwrap_or_default())
+    }
+}
+
+/// Parses a colour in the `#rrggbb` format into its components in the range from 0 to 1.
+fn parse_color(color: &str) -> [f32; 3] {
+    let channel = |index: usize| color.get(1 + 2 * index..3 + 2 * index)
+        .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        .map(|channel| channel as f32 / 255f32)
+        .unwrap_or(1f32);
+    [channel(0), channel(1), channel(2)]
+}